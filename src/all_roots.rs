@@ -0,0 +1,344 @@
+use crate::{Complex, Criteria, NewtonError, Polynomial, Real};
+
+/// The simultaneous iterations an [`AllRoots`] solver can use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AllRootsMethod {
+    /// The Weierstrass, or Durand–Kerner, iteration
+    /// `zᵢ ← zᵢ - p(zᵢ) / (aₙ ∏ⱼ₌₁ⱼ≠ᵢ (zᵢ - zⱼ))`, which converges quadratically to simple roots.
+    DurandKerner,
+    /// The Aberth–Ehrlich iteration `zᵢ ← zᵢ - wᵢ / (1 - wᵢ Σⱼ≠ᵢ 1 / (zᵢ - zⱼ))`, with
+    /// `wᵢ = p(zᵢ) / p'(zᵢ)`, which converges cubically to simple roots.
+    AberthEhrlich,
+}
+
+/// All the roots of a polynomial, found by an [`AllRoots`] solver.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Roots<T> {
+    /// The roots, repeated according to their multiplicity.
+    pub roots: Vec<Complex<T>>,
+    /// The modulus of the polynomial at each root.
+    pub residuals: Vec<T>,
+    /// The number of sweeps after which each root stopped moving.
+    pub converged_after: Vec<usize>,
+    /// The total number of sweeps.
+    pub iterations: usize,
+}
+
+/// Finds all the complex roots of a polynomial at once, by simultaneous iteration.
+///
+/// Deflating the polynomial after each root found by [`Newton`](crate::Newton) accumulates
+/// rounding errors in the later roots. Simultaneous iterations instead refine approximations of
+/// all the roots together on the original polynomial, each one repelled by the others.
+///
+/// The approximations start evenly spaced on a circle whose radius is the Cauchy bound, the
+/// positive root of `|aₙ|xⁿ - Σ |aₖ|xᵏ`, which contains all the roots. Each one is then frozen as
+/// soon as its correction meets the step [`Criteria`], or its residual drops to the level of
+/// rounding errors. The iteration fails with [`NewtonError::MaxIterations`] if some root is still
+/// moving after `max_iterations` sweeps.
+///
+/// # Example
+///
+/// ```
+/// use generic_newton::{AllRoots, Complex, Criteria, Polynomial};
+///
+/// // x³ - 1
+/// let p = Polynomial::new(vec![-1f64, 0., 0., 1.]);
+/// let roots = AllRoots::new(p).solve(&Criteria::default()).unwrap();
+///
+/// for k in 0..3 {
+///     let expected = Complex::from_polar(1., 2. * std::f64::consts::PI * f64::from(k) / 3.);
+///     assert!(roots.roots.iter().any(|&root| (root - expected).norm() < 1E-14));
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct AllRoots<T> {
+    polynomial: Polynomial<T>,
+    method: AllRootsMethod,
+    polish: bool,
+}
+
+impl<T: Real> AllRoots<T> {
+    /// Creates a new solver for the roots of `polynomial`, using the Aberth–Ehrlich iteration
+    /// without polishing.
+    pub fn new(polynomial: Polynomial<T>) -> Self {
+        AllRoots {
+            polynomial,
+            method: AllRootsMethod::AberthEhrlich,
+            polish: false,
+        }
+    }
+
+    /// Sets the simultaneous iteration.
+    pub fn with_method(mut self, method: AllRootsMethod) -> Self {
+        self.method = method;
+        self
+    }
+
+    /// Sets whether to finish with Newton steps on each root.
+    ///
+    /// A polished root is only kept if it reduces the residual, and stays closer to its
+    /// starting point than to any other root.
+    pub fn with_polish(mut self, polish: bool) -> Self {
+        self.polish = polish;
+        self
+    }
+
+    /// Iterates until every root meets `criteria`.
+    ///
+    /// The zero polynomial, which has no coefficient and no isolated roots, is reported as
+    /// [`NewtonError::DimensionMismatch`].
+    pub fn solve(self, criteria: &Criteria<T>) -> Result<Roots<T>, NewtonError<Vec<Complex<T>>>> {
+        let degree = self
+            .polynomial
+            .degree()
+            .ok_or(NewtonError::DimensionMismatch {
+                expected: 1,
+                found: 0,
+            })?;
+        let mut roots = self.initial_roots(degree);
+        let mut converged_after = vec![0; degree];
+        let mut frozen = vec![false; degree];
+
+        let mut iterations = 0;
+        while frozen.contains(&false) {
+            if iterations == criteria.max_iterations {
+                return Err(NewtonError::MaxIterations {
+                    last: roots,
+                    iterations,
+                });
+            }
+            iterations += 1;
+
+            for i in 0..degree {
+                if frozen[i] {
+                    continue;
+                }
+                let previous = roots[i];
+                let next = previous - self.correction(&roots, i);
+                if !next.is_finite() {
+                    return Err(NewtonError::Divergence { at: roots });
+                }
+                roots[i] = next;
+
+                let step_small = (next - previous).norm()
+                    <= criteria.absolute_tolerance + criteria.relative_tolerance * next.norm();
+                let residual = self.polynomial.value(next).norm();
+                if step_small
+                    || residual <= criteria.residual_tolerance
+                    || residual <= self.rounding_error(next)
+                {
+                    frozen[i] = true;
+                    converged_after[i] = iterations;
+                }
+            }
+        }
+
+        if self.polish {
+            for i in 0..degree {
+                roots[i] = self.polished(&roots, i, criteria);
+            }
+        }
+
+        Ok(Roots {
+            residuals: roots
+                .iter()
+                .map(|&root| self.polynomial.value(root).norm())
+                .collect(),
+            roots,
+            converged_after,
+            iterations,
+        })
+    }
+
+    /// Spreads `degree` points on the circle of radius the Cauchy bound.
+    fn initial_roots(&self, degree: usize) -> Vec<Complex<T>> {
+        let coefficients = self.polynomial.coefficients();
+        let leading = coefficients[degree].abs();
+
+        // The Cauchy bound is the positive root of |aₙ|xⁿ - Σₖ₌₀ⁿ⁻¹ |aₖ|xᵏ. Fujiwara's bound
+        // 2 max |aₖ / aₙ|^(1 / (n - k)) lies above it, where Newton's method decreases
+        // monotonically.
+        let mut bound: Vec<T> = coefficients[..degree]
+            .iter()
+            .map(|&c| -c.abs() / leading)
+            .collect();
+        bound.push(T::one());
+        let fujiwara = coefficients[..degree]
+            .iter()
+            .enumerate()
+            .fold(T::zero(), |max, (k, &c)| {
+                let root = (c.abs() / leading).powf(T::one() / T::from_f64((degree - k) as f64));
+                if root > max {
+                    root
+                } else {
+                    max
+                }
+            });
+        let fujiwara = fujiwara + fujiwara;
+        let radius = Polynomial::new(bound)
+            .newton(fujiwara)
+            .solve(&Criteria::default())
+            .map_or(fujiwara, |solution| solution.root);
+        // For aₙxⁿ, every root is zero, but the starting points must be distinct.
+        let radius = if radius > T::zero() { radius } else { T::one() };
+
+        // The offset keeps the points off the real axis, where the conjugate symmetry of real
+        // polynomials could trap them.
+        let turn = T::from_f64(2. * std::f64::consts::PI / degree as f64);
+        (0..degree)
+            .map(|k| Complex::from_polar(radius, turn * T::from_f64(k as f64) + T::from_f64(0.4)))
+            .collect()
+    }
+
+    /// The correction subtracted from the `i`-th root.
+    fn correction(&self, roots: &[Complex<T>], i: usize) -> Complex<T> {
+        let z = roots[i];
+        let others = roots
+            .iter()
+            .enumerate()
+            .filter(|&(j, _)| j != i)
+            .map(|(_, &other)| z - other);
+
+        match self.method {
+            AllRootsMethod::DurandKerner => {
+                let leading = Complex::from(self.polynomial.coefficients()[roots.len()]);
+                let denominator = others.fold(leading, |product, difference| product * difference);
+                self.polynomial.value(z) / denominator
+            }
+            AllRootsMethod::AberthEhrlich => {
+                let (value, derivative) = self.polynomial.evaluate(z);
+                let newton = value / derivative;
+                let repulsion = others.fold(Complex::from(T::zero()), |sum, difference| {
+                    sum + difference.recip()
+                });
+                newton / (Complex::from(T::one()) - newton * repulsion)
+            }
+        }
+    }
+
+    /// A bound on the rounding error of evaluating the polynomial at `z` by Horner's scheme.
+    fn rounding_error(&self, z: Complex<T>) -> T {
+        let modulus = z.norm();
+        let coefficients = self.polynomial.coefficients();
+        let magnitude = coefficients
+            .iter()
+            .rev()
+            .fold(T::zero(), |sum, &c| sum * modulus + c.abs());
+        T::epsilon() * T::from_f64(2. * coefficients.len() as f64) * magnitude
+    }
+
+    /// Refines the `i`-th root with Newton steps on the original polynomial.
+    // `Option::is_none_or` would need Rust 1.82.
+    #[allow(clippy::unnecessary_map_or)]
+    fn polished(&self, roots: &[Complex<T>], i: usize, criteria: &Criteria<T>) -> Complex<T> {
+        let start = roots[i];
+        let residual = self.polynomial.value(start).norm();
+        let separation = roots
+            .iter()
+            .enumerate()
+            .filter(|&(j, _)| j != i)
+            .map(|(_, &other)| (other - start).norm())
+            .fold(None, |min: Option<T>, distance| match min {
+                Some(min) if min < distance => Some(min),
+                _ => Some(distance),
+            });
+
+        let mut newton = self.polynomial.newton(start);
+        let mut polished = start;
+        for step in newton.steps().take(criteria.max_iterations) {
+            if !step.next.is_finite() {
+                return start;
+            }
+            polished = step.next;
+            if step.step.norm()
+                <= criteria.absolute_tolerance + criteria.relative_tolerance * polished.norm()
+            {
+                break;
+            }
+        }
+
+        let stays = separation.map_or(true, |separation| {
+            (polished - start).norm() + (polished - start).norm() < separation
+        });
+        if stays && self.polynomial.value(polished).norm() <= residual {
+            polished
+        } else {
+            start
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{AllRoots, AllRootsMethod};
+    use crate::{Complex, Criteria, NewtonError, Polynomial};
+
+    fn assert_roots(found: &[Complex<f64>], expected: &[Complex<f64>], tolerance: f64) {
+        assert_eq!(found.len(), expected.len());
+        for &root in expected {
+            assert!(
+                found.iter().any(|&z| (z - root).norm() < tolerance),
+                "{:?} not in {:?}",
+                root,
+                found
+            );
+        }
+    }
+
+    #[test]
+    fn methods_agree() {
+        // (x + 3)(x + 1)(x - 0.5)(x - 2)(x - 4)(x² + 1)
+        let p =
+            Polynomial::from_roots(&[-3f64, -1., 0.5, 2., 4.]) * Polynomial::new(vec![1., 0., 1.]);
+        let mut expected: Vec<Complex<f64>> = [-3f64, -1., 0.5, 2., 4.]
+            .iter()
+            .map(|&root| Complex::from(root))
+            .collect();
+        expected.push(Complex::i());
+        expected.push(-Complex::i());
+
+        for &method in &[AllRootsMethod::DurandKerner, AllRootsMethod::AberthEhrlich] {
+            let roots = AllRoots::new(p.clone())
+                .with_method(method)
+                .solve(&Criteria::default())
+                .unwrap();
+            assert_roots(&roots.roots, &expected, 1E-12);
+            assert!(roots.residuals.iter().all(|&residual| residual < 1E-11));
+        }
+
+        let zero = AllRoots::new(Polynomial::new(vec![0f64])).solve(&Criteria::default());
+        assert_eq!(
+            zero,
+            Err(NewtonError::DimensionMismatch {
+                expected: 1,
+                found: 0
+            })
+        );
+    }
+
+    #[test]
+    fn polishing_high_degree() {
+        // Wilkinson's polynomial of degree 12, whose roots are very sensitive to rounding.
+        let expected: Vec<f64> = (1..=12).map(f64::from).collect();
+        let p = Polynomial::from_roots(&expected);
+        let expected: Vec<Complex<f64>> = expected.into_iter().map(Complex::from).collect();
+
+        let rough = AllRoots::new(p.clone())
+            .solve(&Criteria::default())
+            .unwrap();
+        assert_roots(&rough.roots, &expected, 1E-5);
+
+        let polished = AllRoots::new(p)
+            .with_polish(true)
+            .solve(&Criteria::default())
+            .unwrap();
+        assert_roots(&polished.roots, &expected, 1E-5);
+        for (polished, rough) in polished.residuals.iter().zip(&rough.residuals) {
+            assert!(polished <= rough);
+        }
+        let total = |residuals: &[f64]| residuals.iter().sum::<f64>();
+        assert!(total(&polished.residuals) < total(&rough.residuals) / 2.);
+        assert!(polished.converged_after.iter().all(|&sweeps| sweeps > 0));
+    }
+}
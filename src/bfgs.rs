@@ -0,0 +1,530 @@
+use std::cell::Cell;
+use std::collections::VecDeque;
+use std::iter::Iterator;
+
+use crate::linalg::{dot, norm, Matrix};
+use crate::optimize::{failed_search, Advance, Cached};
+use crate::solve::solve_system_with;
+use crate::{Criteria, NewtonError, Optimum, Real, Wolfe};
+
+/// How the approximation of the inverse Hessian is stored.
+enum Memory<T> {
+    /// The full matrix, once the first update has been made.
+    Dense(Option<Matrix<T>>),
+    /// The last pairs of steps and gradient changes, along with `1 / (yᵀ·s)`, oldest first.
+    Limited {
+        history: VecDeque<(Vec<T>, Vec<T>, T)>,
+        size: usize,
+    },
+}
+
+/// Everything about the iteration that is not the current point.
+struct State<T> {
+    line_search: Wolfe<T>,
+    memory: Memory<T>,
+    modifications: usize,
+}
+
+impl<T: Real> State<T> {
+    /// The quasi-Newton direction `-H·gradient`, where `H` approximates the inverse Hessian.
+    fn direction(&self, gradient: &[T]) -> Vec<T> {
+        let product = match &self.memory {
+            Memory::Dense(None) => gradient.to_vec(),
+            Memory::Dense(Some(inverse)) => inverse.mul_vec(gradient),
+            Memory::Limited { history, .. } => {
+                // The two-loop recursion.
+                let mut q = gradient.to_vec();
+                let mut alphas = Vec::with_capacity(history.len());
+                for (s, y, rho) in history.iter().rev() {
+                    let alpha = *rho * dot(s, &q);
+                    axpy(&mut q, -alpha, y);
+                    alphas.push(alpha);
+                }
+
+                if let Some((s, y, _)) = history.back() {
+                    let gamma = dot(s, y) / dot(y, y);
+                    q.iter_mut().for_each(|q| *q = *q * gamma);
+                }
+
+                for ((s, y, rho), alpha) in history.iter().zip(alphas.into_iter().rev()) {
+                    let beta = *rho * dot(y, &q);
+                    axpy(&mut q, alpha - beta, s);
+                }
+                q
+            }
+        };
+        product.into_iter().map(|p| -p).collect()
+    }
+
+    /// Updates the approximation with the step `s` and the change of gradient `y`, unless the
+    /// curvature `yᵀ·s` is not positive, which would break positive definiteness.
+    fn update(&mut self, s: Vec<T>, y: Vec<T>) {
+        let curvature = dot(&y, &s);
+        if curvature <= T::epsilon() * norm(&y) * norm(&s) {
+            self.modifications += 1;
+            return;
+        }
+        let rho = T::one() / curvature;
+
+        match &mut self.memory {
+            Memory::Dense(inverse) => {
+                let n = s.len();
+                // The first approximation is scaled to the curvature along the first step.
+                let h = inverse.get_or_insert_with(|| {
+                    let gamma = curvature / dot(&y, &y);
+                    Matrix::from_fn(n, n, |row, col| if row == col { gamma } else { T::zero() })
+                });
+
+                // H ← (I - ρ·s·yᵀ)·H·(I - ρ·y·sᵀ) + ρ·s·sᵀ, expanded using the symmetry of H.
+                let hy = h.mul_vec(&y);
+                let factor = rho * rho * dot(&y, &hy) + rho;
+                for row in 0..n {
+                    for col in 0..n {
+                        h[(row, col)] = h[(row, col)] - rho * (hy[row] * s[col] + s[row] * hy[col])
+                            + factor * s[row] * s[col];
+                    }
+                }
+            }
+            Memory::Limited { history, size } => {
+                if history.len() == *size {
+                    history.pop_front();
+                }
+                if *size > 0 {
+                    history.push_back((s, y, rho));
+                }
+            }
+        }
+    }
+
+    /// Computes the next iterate from `x`, where `value` and `gradient` are the value and gradient
+    /// of the function at `x`.
+    ///
+    /// `x` is returned when it is a minimiser to working precision, see [`failed_search`].
+    fn advance<O>(&mut self, objective: &O, x: &[T], value: T, gradient: &[T]) -> Advance<T>
+    where
+        O: Fn(&[T]) -> (T, Vec<T>),
+    {
+        let direction = self.direction(gradient);
+        if direction.iter().any(|d| !d.is_finite()) {
+            return Err(NewtonError::NonFiniteDerivative { at: x.to_vec() });
+        }
+
+        // Without curvature information, the first step is scaled to have unit length.
+        let initial = match &self.memory {
+            Memory::Dense(None) => T::one() / norm(gradient),
+            Memory::Limited { history, .. } if history.is_empty() => T::one() / norm(gradient),
+            _ => T::one(),
+        };
+
+        let trial = match self
+            .line_search
+            .search(objective, x, value, gradient, &direction, initial)
+        {
+            Some(trial) => trial,
+            None => return failed_search(x, value, gradient, &direction),
+        };
+
+        let s = trial.point.iter().zip(x).map(|(&a, &b)| a - b).collect();
+        let y = trial
+            .gradient
+            .iter()
+            .zip(gradient)
+            .map(|(&a, &b)| a - b)
+            .collect();
+        self.update(s, y);
+        Ok((trial.point, trial.value, trial.gradient))
+    }
+
+    /// Iterates from `initial_guess` until `criteria` are met.
+    fn solve<F, G>(
+        mut self,
+        criteria: &Criteria<T>,
+        initial_guess: Vec<T>,
+        current_value: Option<(T, Vec<T>)>,
+        func: &F,
+        gradient: &G,
+    ) -> Result<Optimum<T>, NewtonError<Vec<T>>>
+    where
+        F: Fn(&[T]) -> T,
+        G: Fn(&[T]) -> Vec<T>,
+    {
+        let objective = |x: &[T]| (func(x), gradient(x));
+        let cached: Cached<T> = Cell::new(current_value);
+        let value = Cell::new(None);
+
+        let solution = solve_system_with(
+            criteria,
+            initial_guess,
+            |x| {
+                let (fx, gx) = cached.take().unwrap_or_else(|| objective(x));
+                value.set(Some(fx));
+                gx
+            },
+            |x, gx| {
+                let fx = value.get().expect("the residual was computed");
+                let (next, next_value, next_gradient) = self.advance(&objective, x, fx, gx)?;
+                cached.set(Some((next_value, next_gradient)));
+                Ok(next)
+            },
+        )?;
+
+        let fx = value.get().expect("the residual was computed");
+        Ok(Optimum::new(solution, fx, self.modifications))
+    }
+
+    /// Moves `current` to the next iterate.
+    fn next<F, G>(
+        &mut self,
+        current: &mut Vec<T>,
+        current_value: &mut Option<(T, Vec<T>)>,
+        func: &F,
+        gradient: &G,
+    ) -> Option<Vec<T>>
+    where
+        F: Fn(&[T]) -> T,
+        G: Fn(&[T]) -> Vec<T>,
+    {
+        let objective = |x: &[T]| (func(x), gradient(x));
+
+        let (value, grad) = match current_value.take() {
+            Some(cached) => cached,
+            None => objective(current),
+        };
+        let (next, next_value, next_gradient) =
+            self.advance(&objective, current, value, &grad).ok()?;
+
+        *current = next;
+        *current_value = Some((next_value, next_gradient));
+        Some(current.clone())
+    }
+}
+
+/// Computes `y ← y + a·x`.
+fn axpy<T: Real>(y: &mut [T], a: T, x: &[T]) {
+    for (y, &x) in y.iter_mut().zip(x) {
+        *y = *y + a * x;
+    }
+}
+
+/// An iterator that returns successive iterations of the BFGS quasi-Newton method for
+/// unconstrained minimisation.
+///
+/// Only the gradient is needed: an approximation of the inverse of the Hessian is built from the
+/// changes of the gradient along the steps, and each step is found by a strong [`Wolfe`] line
+/// search along the resulting direction. Updates that would make the approximation indefinite
+/// are skipped, and counted in [`Optimum::modifications`].
+///
+/// The approximation is a dense matrix, see [`Lbfgs`] for large problems.
+///
+/// The iterator stops when the direction is not finite, or the line search fails.
+///
+/// # Example
+///
+/// ```
+/// use generic_newton::Bfgs;
+///
+/// // The Rosenbrock function, whose minimum is at (1, 1).
+/// let mut b = Bfgs::new(
+///     vec![-1.2, 1.], // Initial guess
+///     |x: &[f64]| (1. - x[0]).powi(2) + 100. * (x[1] - x[0] * x[0]).powi(2), // The function
+///     |x: &[f64]| vec![
+///         -2. * (1. - x[0]) - 400. * x[0] * (x[1] - x[0] * x[0]),
+///         200. * (x[1] - x[0] * x[0]),
+///     ], // Its gradient
+/// );
+///
+/// let minimiser = b.nth(100).unwrap();
+/// assert!((minimiser[0] - 1.).abs() < 1E-10 && (minimiser[1] - 1.).abs() < 1E-10);
+/// ```
+pub struct Bfgs<T, F, G>
+where
+    T: Real,
+    F: Fn(&[T]) -> T,
+    G: Fn(&[T]) -> Vec<T>,
+{
+    current: Vec<T>,
+    current_value: Option<(T, Vec<T>)>,
+    func: F,
+    gradient: G,
+    state: State<T>,
+}
+
+impl<T, F, G> Bfgs<T, F, G>
+where
+    T: Real,
+    F: Fn(&[T]) -> T,
+    G: Fn(&[T]) -> Vec<T>,
+{
+    /// Creates a new `Bfgs` iterator, using the default [`Wolfe`] line search.
+    ///
+    /// - `func` is the actual function to minimise
+    /// - `gradient` is it's gradient.
+    pub fn new(initial_guess: Vec<T>, func: F, gradient: G) -> Self {
+        Bfgs {
+            current: initial_guess,
+            current_value: None,
+            func,
+            gradient,
+            state: State {
+                line_search: Wolfe::default(),
+                memory: Memory::Dense(None),
+                modifications: 0,
+            },
+        }
+    }
+
+    /// Sets the parameters of the line search.
+    pub fn with_line_search(mut self, line_search: Wolfe<T>) -> Self {
+        self.state.line_search = line_search;
+        self
+    }
+
+    /// Iterates until `criteria` are met, and returns the minimiser found.
+    ///
+    /// The residual tolerance of `criteria` applies to the norm of the gradient. The iteration
+    /// also stops at a minimiser to working precision, where no step can measurably decrease the
+    /// function. Otherwise, a line search that cannot decrease the function fails with
+    /// [`NewtonError::LineSearchFailed`].
+    pub fn solve(self, criteria: &Criteria<T>) -> Result<Optimum<T>, NewtonError<Vec<T>>> {
+        self.state.solve(
+            criteria,
+            self.current,
+            self.current_value,
+            &self.func,
+            &self.gradient,
+        )
+    }
+}
+
+impl<T, F, G> Iterator for Bfgs<T, F, G>
+where
+    T: Real,
+    F: Fn(&[T]) -> T,
+    G: Fn(&[T]) -> Vec<T>,
+{
+    type Item = Vec<T>;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.state.next(
+            &mut self.current,
+            &mut self.current_value,
+            &self.func,
+            &self.gradient,
+        )
+    }
+}
+
+/// An iterator that returns successive iterations of the limited-memory BFGS method for
+/// unconstrained minimisation.
+///
+/// This is [`Bfgs`], except that only the last few steps and changes of gradient are kept, and
+/// the product of the approximate inverse Hessian with the gradient is computed by the two-loop
+/// recursion. Memory and time per iteration are then linear in the number of variables.
+///
+/// The iterator stops when the direction is not finite, or the line search fails.
+///
+/// # Example
+///
+/// ```
+/// use generic_newton::Lbfgs;
+///
+/// // A quadratic in 100 variables, whose minimum is at (1, 2, 3…).
+/// let func = |x: &[f64]| -> f64 {
+///     (1..=100).zip(x).map(|(i, x)| i as f64 * (x - i as f64).powi(2)).sum()
+/// };
+/// let gradient = |x: &[f64]| -> Vec<f64> {
+///     (1..=100).zip(x).map(|(i, x)| 2. * i as f64 * (x - i as f64)).collect()
+/// };
+///
+/// let mut l = Lbfgs::new(vec![0.; 100], func, gradient).with_history(5);
+///
+/// let minimiser = l.nth(200).unwrap();
+/// assert!((minimiser[99] - 100.).abs() < 1E-8);
+/// ```
+pub struct Lbfgs<T, F, G>
+where
+    T: Real,
+    F: Fn(&[T]) -> T,
+    G: Fn(&[T]) -> Vec<T>,
+{
+    current: Vec<T>,
+    current_value: Option<(T, Vec<T>)>,
+    func: F,
+    gradient: G,
+    state: State<T>,
+}
+
+impl<T, F, G> Lbfgs<T, F, G>
+where
+    T: Real,
+    F: Fn(&[T]) -> T,
+    G: Fn(&[T]) -> Vec<T>,
+{
+    /// Creates a new `Lbfgs` iterator, keeping the last 10 steps and using the default [`Wolfe`]
+    /// line search.
+    ///
+    /// - `func` is the actual function to minimise
+    /// - `gradient` is it's gradient.
+    pub fn new(initial_guess: Vec<T>, func: F, gradient: G) -> Self {
+        Lbfgs {
+            current: initial_guess,
+            current_value: None,
+            func,
+            gradient,
+            state: State {
+                line_search: Wolfe::default(),
+                memory: Memory::Limited {
+                    history: VecDeque::new(),
+                    size: 10,
+                },
+                modifications: 0,
+            },
+        }
+    }
+
+    /// Sets the number of steps kept.
+    pub fn with_history(mut self, size: usize) -> Self {
+        if let Memory::Limited { history, .. } = &mut self.state.memory {
+            while history.len() > size {
+                history.pop_front();
+            }
+        }
+        if let Memory::Limited { size: old, .. } = &mut self.state.memory {
+            *old = size;
+        }
+        self
+    }
+
+    /// Sets the parameters of the line search.
+    pub fn with_line_search(mut self, line_search: Wolfe<T>) -> Self {
+        self.state.line_search = line_search;
+        self
+    }
+
+    /// Iterates until `criteria` are met, and returns the minimiser found.
+    ///
+    /// The residual tolerance of `criteria` applies to the norm of the gradient. The iteration
+    /// also stops at a minimiser to working precision, where no step can measurably decrease the
+    /// function. Otherwise, a line search that cannot decrease the function fails with
+    /// [`NewtonError::LineSearchFailed`].
+    pub fn solve(self, criteria: &Criteria<T>) -> Result<Optimum<T>, NewtonError<Vec<T>>> {
+        self.state.solve(
+            criteria,
+            self.current,
+            self.current_value,
+            &self.func,
+            &self.gradient,
+        )
+    }
+}
+
+impl<T, F, G> Iterator for Lbfgs<T, F, G>
+where
+    T: Real,
+    F: Fn(&[T]) -> T,
+    G: Fn(&[T]) -> Vec<T>,
+{
+    type Item = Vec<T>;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.state.next(
+            &mut self.current,
+            &mut self.current_value,
+            &self.func,
+            &self.gradient,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{Bfgs, Lbfgs};
+    use crate::{Criteria, Matrix, NewtonError, NewtonOptimizer};
+
+    /// The extended Rosenbrock function.
+    fn func(x: &[f64]) -> f64 {
+        x.chunks(2)
+            .map(|p| (1. - p[0]).powi(2) + 100. * (p[1] - p[0] * p[0]).powi(2))
+            .sum()
+    }
+
+    fn gradient(x: &[f64]) -> Vec<f64> {
+        x.chunks(2)
+            .flat_map(|p| {
+                vec![
+                    -2. * (1. - p[0]) - 400. * p[0] * (p[1] - p[0] * p[0]),
+                    200. * (p[1] - p[0] * p[0]),
+                ]
+            })
+            .collect()
+    }
+
+    fn start() -> Vec<f64> {
+        (0..10)
+            .map(|i| if i % 2 == 0 { -1.2 } else { 1. })
+            .collect()
+    }
+
+    #[test]
+    fn quasi_newton_converge() {
+        let criteria = Criteria {
+            residual_tolerance: 1E-8,
+            max_iterations: 1000,
+            ..Criteria::default()
+        };
+
+        let dense = Bfgs::new(start(), func, gradient).solve(&criteria).unwrap();
+        let limited = Lbfgs::new(start(), func, gradient)
+            .with_history(7)
+            .solve(&criteria)
+            .unwrap();
+
+        for optimum in &[&dense, &limited] {
+            assert!(optimum.gradient_norm <= 1E-8);
+            assert!(optimum.minimiser.iter().all(|x| (x - 1.).abs() < 1E-6));
+            assert_eq!(optimum.modifications, 0);
+        }
+    }
+
+    #[test]
+    fn matches_newton_on_quadratic() {
+        let a = Matrix::from_rows(&[[3., 1., 0.], [1., 4., 2.], [0., 2., 5.]]);
+        let b = [1., -2., 3.];
+        let func = |x: &[f64]| {
+            0.5 * a
+                .mul_vec(x)
+                .iter()
+                .zip(x)
+                .map(|(ax, x)| ax * x)
+                .sum::<f64>()
+                - b.iter().zip(x).map(|(b, x)| b * x).sum::<f64>()
+        };
+        let grad = |x: &[f64]| a.mul_vec(x).iter().zip(&b).map(|(ax, b)| ax - b).collect();
+
+        let exact = NewtonOptimizer::new(vec![0.; 3], func, grad, |_: &[f64]| a.clone())
+            .solve(&Criteria::default())
+            .unwrap();
+        let quasi = Bfgs::new(vec![0.; 3], func, grad)
+            .solve(&Criteria::default())
+            .unwrap();
+        for (e, q) in exact.minimiser.iter().zip(&quasi.minimiser) {
+            assert!((e - q).abs() < 1E-8);
+        }
+    }
+
+    #[test]
+    fn reports_failed_line_search() {
+        // A gradient of the wrong sign makes every direction an ascent one.
+        let func = |x: &[f64]| x[0] * x[0];
+        let wrong = |x: &[f64]| vec![-2. * x[0]];
+        let failure = Err(NewtonError::LineSearchFailed { at: vec![1.] });
+
+        assert_eq!(
+            Bfgs::new(vec![1.], func, wrong).solve(&Criteria::default()),
+            failure
+        );
+        assert_eq!(
+            Lbfgs::new(vec![1.], func, wrong).solve(&Criteria::default()),
+            failure
+        );
+    }
+}
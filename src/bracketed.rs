@@ -0,0 +1,213 @@
+use std::iter::Iterator;
+
+use crate::solve::solve_with;
+use crate::{Criteria, NewtonError, Real, Solution};
+
+/// An iterator that returns successive iterations of a safeguarded Newton's method.
+///
+/// The root is kept inside an interval where the function changes sign. Newton steps are taken
+/// when they stay inside this interval and at least halve the previous step, otherwise the
+/// interval is bisected, which guarantees convergence.
+///
+/// # Example
+///
+/// ```
+/// use generic_newton::BracketedNewton;
+///
+/// let mut n = BracketedNewton::new(
+///     0., // Lower end of the interval
+///     2., // Upper end of the interval
+///     |x: f64| x.cos() - x.powi(3), // The actual function
+///     |x| -(x.sin() + 3. * x.powi(2)), // Its derivative
+/// )
+/// .unwrap();
+///
+/// assert!((n.nth(100).unwrap() - 0.865474033102).abs() < 1E-11)
+/// ```
+pub struct BracketedNewton<T, F, DF>
+where
+    T: Real,
+    F: Fn(T) -> T,
+    DF: Fn(T) -> T,
+{
+    state: Bracket<T>,
+    current: T,
+    func: F,
+    derivative: DF,
+}
+
+/// The interval around the root, along with the last two step sizes.
+#[derive(Clone, Copy)]
+struct Bracket<T> {
+    /// The end where the function is non-positive.
+    negative: T,
+    /// The end where the function is non-negative.
+    positive: T,
+    step: T,
+    previous_step: T,
+}
+
+impl<T: Real> Bracket<T> {
+    /// Shrinks the interval using the value at `x`, and returns the next iterate.
+    fn advance(&mut self, x: T, value: T, derivative: T) -> T {
+        if value == T::zero() {
+            return x;
+        }
+
+        if value < T::zero() {
+            self.negative = x;
+        } else {
+            self.positive = x;
+        }
+
+        let two = T::from_f64(2.);
+        let outside = ((x - self.positive) * derivative - value)
+            * ((x - self.negative) * derivative - value)
+            > T::zero();
+        let too_slow = (two * value).abs() > (self.previous_step * derivative).abs();
+
+        self.previous_step = self.step;
+        if !derivative.is_finite() || outside || too_slow {
+            self.step = (self.positive - self.negative) / two;
+            self.negative + self.step
+        } else {
+            self.step = value / derivative;
+            x - self.step
+        }
+    }
+}
+
+impl<T, F, DF> BracketedNewton<T, F, DF>
+where
+    T: Real,
+    F: Fn(T) -> T,
+    DF: Fn(T) -> T,
+{
+    /// Creates a new `BracketedNewton` iterator, starting at the middle of the interval.
+    ///
+    /// - `lower` and `upper` are the ends of an interval where `func` changes sign
+    /// - `func` is the actual function to find the root of
+    /// - `derivative` is it's derivative.
+    ///
+    /// Fails with [`NewtonError::NoSignChange`] if `func` has the same sign at both ends.
+    pub fn new(lower: T, upper: T, func: F, derivative: DF) -> Result<Self, NewtonError<T>> {
+        let lower_value = func(lower);
+        let upper_value = func(upper);
+        if lower_value.is_nan() {
+            return Err(NewtonError::NanResidual { at: lower });
+        }
+        if upper_value.is_nan() {
+            return Err(NewtonError::NanResidual { at: upper });
+        }
+
+        let zero = T::zero();
+        if (lower_value > zero && upper_value > zero) || (lower_value < zero && upper_value < zero)
+        {
+            return Err(NewtonError::NoSignChange { lower, upper });
+        }
+
+        let (negative, positive) = if lower_value < zero || upper_value > zero {
+            (lower, upper)
+        } else {
+            (upper, lower)
+        };
+        let width = (upper - lower).abs();
+
+        Ok(BracketedNewton {
+            state: Bracket {
+                negative,
+                positive,
+                step: width,
+                previous_step: width,
+            },
+            current: (lower + upper) / T::from_f64(2.),
+            func,
+            derivative,
+        })
+    }
+
+    /// The current interval containing the root, as `(lower, upper)`.
+    pub fn bracket(&self) -> (T, T) {
+        let Bracket {
+            negative, positive, ..
+        } = self.state;
+        if negative < positive {
+            (negative, positive)
+        } else {
+            (positive, negative)
+        }
+    }
+
+    /// Iterates until `criteria` are met, and returns the root found.
+    pub fn solve(self, criteria: &Criteria<T>) -> Result<Solution<T>, NewtonError<T>> {
+        let deriv = &self.derivative;
+        let mut state = self.state;
+
+        solve_with(criteria, self.current, &self.func, |x, fx| {
+            Ok(state.advance(x, fx, deriv(x)))
+        })
+    }
+}
+
+impl<T, F, DF> Iterator for BracketedNewton<T, F, DF>
+where
+    T: Real,
+    F: Fn(T) -> T,
+    DF: Fn(T) -> T,
+{
+    type Item = T;
+    fn next(&mut self) -> Option<Self::Item> {
+        let func = &self.func;
+        let deriv = &self.derivative;
+
+        let next = self
+            .state
+            .advance(self.current, func(self.current), deriv(self.current));
+
+        self.current = next;
+        Some(next)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::BracketedNewton;
+    use crate::{Criteria, Newton, NewtonError};
+
+    #[test]
+    fn stays_in_bracket() {
+        // Plain Newton is thrown away from the root by the flat tails of `atan`.
+        let mut n = Newton::new(2., |x: f64| x.atan(), |x| 1. / (1. + x * x));
+        assert!(n.nth(1).unwrap().abs() > 10.);
+
+        let mut n =
+            BracketedNewton::new(-1., 10., |x: f64| x.atan(), |x| 1. / (1. + x * x)).unwrap();
+        for _ in 0..20 {
+            let x = n.next().unwrap();
+            let (lower, upper) = n.bracket();
+            assert!(-1. <= lower && lower <= x && x <= upper && upper <= 10.);
+        }
+        assert!(n.next().unwrap().abs() < 1E-15);
+    }
+
+    #[test]
+    fn survives_zero_derivative() {
+        let solution = BracketedNewton::new(-2., 2., |x: f64| x.powi(3) - 1., |x| 3. * x * x)
+            .unwrap()
+            .solve(&Criteria::default())
+            .unwrap();
+        assert!((solution.root - 1.).abs() < 1E-14);
+    }
+
+    #[test]
+    fn needs_sign_change() {
+        assert!(matches!(
+            BracketedNewton::new(-1., 1., |x: f64| x * x + 1., |x| 2. * x),
+            Err(NewtonError::NoSignChange {
+                lower: -1.,
+                upper: 1.
+            })
+        ));
+    }
+}
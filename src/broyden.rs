@@ -0,0 +1,295 @@
+use std::cell::Cell;
+use std::iter::Iterator;
+
+use crate::linalg::{dot, Lu, Matrix};
+use crate::solve::solve_system_with;
+use crate::system::finite_difference_jacobian;
+use crate::{Criteria, NewtonError, Real, Solution};
+
+/// The rank-one update applied to the approximate Jacobian after each step.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BroydenUpdate {
+    /// Broyden's first method, which minimises the change of the Jacobian.
+    Good,
+    /// Broyden's second method, which minimises the change of the inverse Jacobian.
+    Bad,
+}
+
+/// How the approximate Jacobian is initialised.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InitialJacobian<T> {
+    /// Starts from the identity matrix, without evaluating the function.
+    Identity,
+    /// Starts from forward finite differences with the given relative step, which costs one
+    /// evaluation of the function per variable.
+    FiniteDifferences(T),
+}
+
+/// The current approximation, either of the Jacobian or of its inverse.
+#[derive(Debug, Clone)]
+enum Approximation<T> {
+    Jacobian(Matrix<T>),
+    Inverse(Matrix<T>),
+}
+
+/// The next iterate and the value of the function there, or why no step could be taken.
+type Advance<T> = Result<(Vec<T>, Vec<T>), NewtonError<Vec<T>>>;
+
+/// Everything about the iteration that is not the current point.
+struct State<T> {
+    update: BroydenUpdate,
+    initial: InitialJacobian<T>,
+    inverse_updates: bool,
+    approximation: Option<Approximation<T>>,
+}
+
+impl<T: Real> State<T> {
+    /// Takes a step from `x` and updates the approximation.
+    fn advance<F: Fn(&[T]) -> Vec<T>>(&mut self, func: &F, x: &[T], fx: &[T]) -> Advance<T> {
+        let singular = || NewtonError::ZeroDerivative { at: x.to_vec() };
+
+        let approximation = match self.approximation.take() {
+            Some(approximation) => approximation,
+            None => {
+                let jacobian = match self.initial {
+                    InitialJacobian::Identity => Matrix::identity(x.len()),
+                    InitialJacobian::FiniteDifferences(step) => {
+                        finite_difference_jacobian(func, x, fx, step)
+                    }
+                };
+                if !jacobian.is_finite() {
+                    return Err(NewtonError::NonFiniteDerivative { at: x.to_vec() });
+                }
+                if self.inverse_updates {
+                    Approximation::Inverse(Lu::new(&jacobian).ok_or_else(singular)?.inverse())
+                } else {
+                    Approximation::Jacobian(jacobian)
+                }
+            }
+        };
+
+        let direction = match &approximation {
+            Approximation::Jacobian(jacobian) => Lu::new(jacobian).ok_or_else(singular)?.solve(fx),
+            Approximation::Inverse(inverse) => inverse.mul_vec(fx),
+        };
+
+        let next: Vec<T> = x.iter().zip(&direction).map(|(&x, &d)| x - d).collect();
+        let next_value = func(&next);
+        let s: Vec<T> = direction.iter().map(|&d| -d).collect();
+        let y: Vec<T> = next_value.iter().zip(fx).map(|(&n, &f)| n - f).collect();
+
+        self.approximation = Some(match approximation {
+            Approximation::Jacobian(mut jacobian) => {
+                // B += (y - B·s)·vᵀ / (vᵀ·s), with v = s or Bᵀ·y.
+                let v = match self.update {
+                    BroydenUpdate::Good => s.clone(),
+                    BroydenUpdate::Bad => jacobian.transpose().mul_vec(&y),
+                };
+                let bs = jacobian.mul_vec(&s);
+                let u: Vec<T> = y.iter().zip(&bs).map(|(&y, &bs)| y - bs).collect();
+                rank_one_update(&mut jacobian, &u, &v, dot(&v, &s));
+                Approximation::Jacobian(jacobian)
+            }
+            Approximation::Inverse(mut inverse) => {
+                // H += (s - H·y)·vᵀ / (vᵀ·y), with v = Hᵀ·s or y.
+                let v = match self.update {
+                    BroydenUpdate::Good => inverse.transpose().mul_vec(&s),
+                    BroydenUpdate::Bad => y.clone(),
+                };
+                let hy = inverse.mul_vec(&y);
+                let u: Vec<T> = s.iter().zip(&hy).map(|(&s, &hy)| s - hy).collect();
+                rank_one_update(&mut inverse, &u, &v, dot(&v, &y));
+                Approximation::Inverse(inverse)
+            }
+        });
+
+        Ok((next, next_value))
+    }
+}
+
+/// Adds `u·vᵀ / denominator` to `matrix`, unless `denominator` is zero.
+fn rank_one_update<T: Real>(matrix: &mut Matrix<T>, u: &[T], v: &[T], denominator: T) {
+    if denominator == T::zero() {
+        return;
+    }
+    for (row, &u) in u.iter().enumerate() {
+        let factor = u / denominator;
+        for (col, &v) in v.iter().enumerate() {
+            matrix[(row, col)] = matrix[(row, col)] + factor * v;
+        }
+    }
+}
+
+/// An iterator that returns successive iterations of Broyden's quasi-Newton method for systems.
+///
+/// The Jacobian is never evaluated: an approximation is corrected by a rank-one update after each
+/// step, using only the values of the function, which is evaluated once per iteration. With
+/// inverse updates the approximation of the inverse Jacobian is updated directly through the
+/// Sherman–Morrison formula, so that each step costs `O(n²)` instead of solving a linear system.
+///
+/// The iterator stops when the approximate Jacobian is singular.
+///
+/// # Example
+///
+/// ```
+/// use generic_newton::{Broyden, InitialJacobian};
+///
+/// // The intersections of the unit circle and the line y = x.
+/// let mut b = Broyden::new(
+///     vec![1., 0.], // Initial guess
+///     |x: &[f64]| vec![x[0] * x[0] + x[1] * x[1] - 1., x[0] - x[1]], // The actual function
+/// )
+/// .with_initial_jacobian(InitialJacobian::FiniteDifferences(1E-7));
+///
+/// let root = b.nth(20).unwrap();
+/// assert!((root[0] - 0.5f64.sqrt()).abs() < 1E-12);
+/// assert!((root[1] - 0.5f64.sqrt()).abs() < 1E-12);
+/// ```
+pub struct Broyden<T, F>
+where
+    T: Real,
+    F: Fn(&[T]) -> Vec<T>,
+{
+    current: Vec<T>,
+    current_value: Option<Vec<T>>,
+    func: F,
+    state: State<T>,
+}
+
+impl<T, F> Broyden<T, F>
+where
+    T: Real,
+    F: Fn(&[T]) -> Vec<T>,
+{
+    /// Creates a new `Broyden` iterator, using the good update on the Jacobian, initialised to the
+    /// identity.
+    ///
+    /// - `func` is the actual function to find the root of
+    pub fn new(initial_guess: Vec<T>, func: F) -> Self {
+        Broyden {
+            current: initial_guess,
+            current_value: None,
+            func,
+            state: State {
+                update: BroydenUpdate::Good,
+                initial: InitialJacobian::Identity,
+                inverse_updates: false,
+                approximation: None,
+            },
+        }
+    }
+
+    /// Sets the rank-one update to use.
+    pub fn with_update(mut self, update: BroydenUpdate) -> Self {
+        self.state.update = update;
+        self
+    }
+
+    /// Sets how the Jacobian is initialised.
+    pub fn with_initial_jacobian(mut self, initial: InitialJacobian<T>) -> Self {
+        self.state.initial = initial;
+        self
+    }
+
+    /// Sets whether the inverse Jacobian is updated instead of the Jacobian.
+    pub fn with_inverse_updates(mut self, inverse_updates: bool) -> Self {
+        self.state.inverse_updates = inverse_updates;
+        self
+    }
+
+    /// Iterates until `criteria` are met, and returns the root found.
+    ///
+    /// A singular approximate Jacobian is reported as [`NewtonError::ZeroDerivative`].
+    pub fn solve(self, criteria: &Criteria<T>) -> Result<Solution<Vec<T>>, NewtonError<Vec<T>>> {
+        let func = &self.func;
+        let mut state = self.state;
+        let cached = Cell::new(self.current_value);
+
+        solve_system_with(
+            criteria,
+            self.current,
+            |x| cached.take().unwrap_or_else(|| func(x)),
+            |x, fx| {
+                let (next, next_value) = state.advance(func, x, fx)?;
+                cached.set(Some(next_value));
+                Ok(next)
+            },
+        )
+    }
+}
+
+impl<T, F> Iterator for Broyden<T, F>
+where
+    T: Real,
+    F: Fn(&[T]) -> Vec<T>,
+{
+    type Item = Vec<T>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let func = &self.func;
+
+        let value = match self.current_value.take() {
+            Some(value) => value,
+            None => func(&self.current),
+        };
+        let (next, next_value) = self.state.advance(func, &self.current, &value).ok()?;
+
+        self.current = next;
+        self.current_value = Some(next_value);
+        Some(self.current.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{Broyden, BroydenUpdate, InitialJacobian};
+    use crate::Criteria;
+
+    fn func(x: &[f64]) -> Vec<f64> {
+        vec![
+            x[0] + 0.5 * (x[0] - x[1]).powi(3) - 1.,
+            0.5 * (x[1] - x[0]).powi(3) + x[1],
+        ]
+    }
+
+    #[test]
+    fn all_variants_converge() {
+        let criteria = Criteria {
+            max_iterations: 50,
+            ..Criteria::default()
+        };
+
+        for &update in &[BroydenUpdate::Good, BroydenUpdate::Bad] {
+            for &initial in &[
+                InitialJacobian::Identity,
+                InitialJacobian::FiniteDifferences(1E-7),
+            ] {
+                for &inverse in &[false, true] {
+                    let solution = Broyden::new(vec![0., 0.], func)
+                        .with_update(update)
+                        .with_initial_jacobian(initial)
+                        .with_inverse_updates(inverse)
+                        .solve(&criteria)
+                        .unwrap();
+
+                    assert!(solution.residual.iter().all(|r| r.abs() < 1E-12));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn inverse_updates_match() {
+        let direct: Vec<_> = Broyden::new(vec![0., 0.], func).take(5).collect();
+        let inverse: Vec<_> = Broyden::new(vec![0., 0.], func)
+            .with_inverse_updates(true)
+            .take(5)
+            .collect();
+
+        for (d, i) in direct.iter().zip(&inverse) {
+            for (d, i) in d.iter().zip(i) {
+                assert!((d - i).abs() < 1E-12);
+            }
+        }
+    }
+}
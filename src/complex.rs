@@ -0,0 +1,243 @@
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+use crate::Real;
+
+/// A complex number `re + im·i`.
+///
+/// The plain [`Newton`](crate::Newton) iterator only needs `Div` and `Sub`, so it runs unchanged
+/// in the complex plane, where it can reach the roots real iterates never see.
+///
+/// # Example
+///
+/// ```
+/// use generic_newton::{Complex, Newton};
+///
+/// let one = Complex::new(1., 0.);
+/// let mut n = Newton::new(
+///     Complex::new(-1f64, 1.),
+///     |z| z * z * z - one,
+///     |z| z * z * 3.,
+/// );
+///
+/// let root = n.nth(50).unwrap();
+/// assert!((root - Complex::new(-0.5, 3f64.sqrt() / 2.)).norm() < 1E-12);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Complex<T> {
+    /// The real part.
+    pub re: T,
+    /// The imaginary part.
+    pub im: T,
+}
+
+impl<T> Complex<T> {
+    /// Creates a new complex number.
+    pub fn new(re: T, im: T) -> Self {
+        Complex { re, im }
+    }
+}
+
+impl<T: Real> Complex<T> {
+    /// The imaginary unit.
+    pub fn i() -> Self {
+        Complex::new(T::zero(), T::one())
+    }
+
+    /// Creates a complex number of modulus `norm` and argument `arg`, in radians.
+    pub fn from_polar(norm: T, arg: T) -> Self {
+        Complex::new(norm * arg.cos(), norm * arg.sin())
+    }
+
+    /// The complex conjugate `re - im·i`.
+    pub fn conj(self) -> Self {
+        Complex::new(self.re, -self.im)
+    }
+
+    /// The squared modulus, which avoids a square root.
+    pub fn norm_sqr(self) -> T {
+        self.re * self.re + self.im * self.im
+    }
+
+    /// The modulus.
+    pub fn norm(self) -> T {
+        let (re, im) = (self.re.abs(), self.im.abs());
+        let (large, small) = if re < im { (im, re) } else { (re, im) };
+        if large == T::zero() {
+            return T::zero();
+        }
+        let ratio = small / large;
+        large * (T::one() + ratio * ratio).sqrt()
+    }
+
+    /// Whether both parts are neither infinite nor NaN.
+    pub fn is_finite(self) -> bool {
+        self.re.is_finite() && self.im.is_finite()
+    }
+
+    /// Whether either part is NaN.
+    pub fn is_nan(self) -> bool {
+        self.re.is_nan() || self.im.is_nan()
+    }
+
+    /// The reciprocal `1 / self`.
+    pub fn recip(self) -> Self {
+        Complex::new(T::one(), T::zero()) / self
+    }
+
+    /// The principal square root, whose real part is non-negative.
+    pub fn sqrt(self) -> Self {
+        let norm = self.norm();
+        let half = T::from_f64(0.5);
+        let re = ((norm + self.re) * half).sqrt();
+        let im = ((norm - self.re) * half).sqrt();
+        Complex::new(re, if self.im < T::zero() { -im } else { im })
+    }
+
+    /// Raises to an integer power, by repeated squaring.
+    pub fn powi(self, n: i32) -> Self {
+        let mut base = if n < 0 { self.recip() } else { self };
+        let mut exponent = n.unsigned_abs();
+        let mut power = Complex::new(T::one(), T::zero());
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                power = power * base;
+            }
+            base = base * base;
+            exponent >>= 1;
+        }
+        power
+    }
+
+    /// The exponential.
+    pub fn exp(self) -> Self {
+        Complex::from_polar(self.re.exp(), self.im)
+    }
+}
+
+impl<T: Real> From<T> for Complex<T> {
+    fn from(re: T) -> Self {
+        Complex::new(re, T::zero())
+    }
+}
+
+impl<T: Add<Output = T>> Add for Complex<T> {
+    type Output = Self;
+    fn add(self, other: Self) -> Self {
+        Complex::new(self.re + other.re, self.im + other.im)
+    }
+}
+
+impl<T: Add<Output = T>> Add<T> for Complex<T> {
+    type Output = Self;
+    fn add(self, other: T) -> Self {
+        Complex::new(self.re + other, self.im)
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Complex<T> {
+    type Output = Self;
+    fn sub(self, other: Self) -> Self {
+        Complex::new(self.re - other.re, self.im - other.im)
+    }
+}
+
+impl<T: Sub<Output = T>> Sub<T> for Complex<T> {
+    type Output = Self;
+    fn sub(self, other: T) -> Self {
+        Complex::new(self.re - other, self.im)
+    }
+}
+
+impl<T: Add<Output = T> + Sub<Output = T> + Mul<Output = T> + Copy> Mul for Complex<T> {
+    type Output = Self;
+    fn mul(self, other: Self) -> Self {
+        Complex::new(
+            self.re * other.re - self.im * other.im,
+            self.re * other.im + self.im * other.re,
+        )
+    }
+}
+
+impl<T: Mul<Output = T> + Copy> Mul<T> for Complex<T> {
+    type Output = Self;
+    fn mul(self, other: T) -> Self {
+        Complex::new(self.re * other, self.im * other)
+    }
+}
+
+impl<T: Real> Div for Complex<T> {
+    type Output = Self;
+    fn div(self, other: Self) -> Self {
+        // Smith's algorithm, which scales by the larger part of the divisor to avoid overflow.
+        if other.im.abs() <= other.re.abs() {
+            let ratio = other.im / other.re;
+            let denominator = other.re + other.im * ratio;
+            Complex::new(
+                (self.re + self.im * ratio) / denominator,
+                (self.im - self.re * ratio) / denominator,
+            )
+        } else {
+            let ratio = other.re / other.im;
+            let denominator = other.re * ratio + other.im;
+            Complex::new(
+                (self.re * ratio + self.im) / denominator,
+                (self.im * ratio - self.re) / denominator,
+            )
+        }
+    }
+}
+
+impl<T: Div<Output = T> + Copy> Div<T> for Complex<T> {
+    type Output = Self;
+    fn div(self, other: T) -> Self {
+        Complex::new(self.re / other, self.im / other)
+    }
+}
+
+impl<T: Neg<Output = T>> Neg for Complex<T> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Complex::new(-self.re, -self.im)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::Complex;
+    use crate::Newton;
+
+    fn assert_close(a: Complex<f64>, b: Complex<f64>) {
+        assert!((a - b).norm() < 1E-14, "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn arithmetic() {
+        let i = Complex::<f64>::i();
+        let z = Complex::new(3f64, -4.);
+
+        assert_close(i * i, Complex::from(-1.));
+        assert_eq!(z.norm(), 5.);
+        assert_close(z * z.recip(), Complex::from(1.));
+        assert_close(z / Complex::new(0., 2.), Complex::new(-2., -1.5));
+        assert_close(z.sqrt() * z.sqrt(), z);
+        assert_close(z.powi(3), z * z * z);
+        assert_close(z.powi(-2) * z * z, Complex::from(1.));
+        assert_close((i * std::f64::consts::PI).exp(), Complex::from(-1.));
+        assert_close(z * z.conj(), Complex::from(z.norm_sqr()));
+    }
+
+    #[test]
+    fn cube_roots_of_unity() {
+        for k in 0..3 {
+            let root = Complex::from_polar(1f64, 2. * std::f64::consts::PI * f64::from(k) / 3.);
+            let mut n: Newton<Complex<f64>, _, _> = Newton::new(
+                root * 1.3 + Complex::new(0.1, -0.1),
+                |z| z.powi(3) - 1.,
+                |z| z * z * 3.,
+            );
+
+            assert_close(n.nth(50).unwrap(), root);
+        }
+    }
+}
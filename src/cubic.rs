@@ -0,0 +1,535 @@
+use std::cell::Cell;
+use std::iter::Iterator;
+
+use crate::linalg::{dot, norm, Matrix, SymmetricEigen};
+use crate::optimize::{failed_search, Advance, Cached};
+use crate::solve::solve_system_with;
+use crate::{Criteria, NewtonError, Real, Solution};
+
+/// How [`AdaptiveCubic`] minimises the cubic model at each iteration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CubicSubproblem {
+    /// Minimises the model exactly, using the eigendecomposition of the Hessian. This handles
+    /// saddle points, where the gradient is orthogonal to the directions of negative curvature.
+    Eigen,
+    /// Minimises the model on the Krylov subspace of the given dimension spanned by the gradient,
+    /// found by the Lanczos process, which only needs products of the Hessian with vectors.
+    Lanczos(usize),
+}
+
+/// The result of [`AdaptiveCubic::solve`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct CubicOptimum<T> {
+    /// The minimiser found.
+    pub minimiser: Vec<T>,
+    /// The value of the function at `minimiser`.
+    pub value: T,
+    /// The gradient at `minimiser`.
+    pub gradient: Vec<T>,
+    /// The norm of `gradient`.
+    pub gradient_norm: T,
+    /// The number of iterations performed.
+    pub iterations: usize,
+    /// The last regularisation parameter `σ`.
+    pub regularisation: T,
+    /// The smallest eigenvalue of the Hessian at `minimiser`.
+    pub smallest_eigenvalue: T,
+    /// Whether `minimiser` satisfies the second-order conditions, that is whether the Hessian
+    /// there is positive semi-definite, up to `√ε` times its largest eigenvalue.
+    pub second_order: bool,
+}
+
+/// Minimises the cubic model `gᵀ·s + ½·sᵀ·H·s + σ/3·‖s‖³` given the eigendecomposition of `H`,
+/// and returns `s` in the basis of its eigenvectors.
+///
+/// The minimiser is `-(H + λ·I)⁻¹·g`, where `λ = σ·‖s‖` makes `H + λ·I` positive semi-definite.
+/// This equation is solved by bisection, since `‖s‖` decreases with `λ`. In the hard case,
+/// where `g` is orthogonal to the eigenvectors of the smallest eigenvalue, a multiple of one of
+/// them is added to reach the required norm.
+fn cubic_step<T: Real>(values: &[T], gradient: &[T], regularisation: T) -> Vec<T> {
+    let smallest = values
+        .iter()
+        .fold(T::zero(), |acc, &v| if v < acc { v } else { acc });
+    let lower = -smallest;
+    let step = |shift: T| -> Vec<T> {
+        values
+            .iter()
+            .zip(gradient)
+            .map(|(&v, &g)| {
+                if v + shift > T::zero() {
+                    -g / (v + shift)
+                } else {
+                    T::zero()
+                }
+            })
+            .collect()
+    };
+
+    // The hard case.
+    let scale = norm(gradient);
+    let tolerance = T::epsilon() * (T::one() + scale) * (T::one() + lower.abs());
+    let degenerate = |(&v, g): (&T, &T)| v + lower <= tolerance && g.abs() <= T::epsilon() * scale;
+    if values
+        .iter()
+        .zip(gradient)
+        .filter(|&(&v, _)| v + lower <= tolerance)
+        .all(degenerate)
+    {
+        let mut s = step(lower);
+        let required = lower / regularisation;
+        let missing = required * required - dot(&s, &s);
+        if missing > T::zero() {
+            let index = values
+                .iter()
+                .position(|&v| v + lower <= tolerance)
+                .expect("the smallest eigenvalue is there");
+            s[index] = s[index] + missing.sqrt();
+            return s;
+        }
+    }
+
+    let residual = |shift: T| norm(&step(shift)) - shift / regularisation;
+    let mut low = lower;
+    let mut width = T::one();
+    while residual(lower + width) > T::zero() {
+        low = lower + width;
+        width = width + width;
+    }
+    let mut high = lower + width;
+    for _ in 0..200 {
+        let middle = T::from_f64(0.5) * (low + high);
+        if middle <= low || middle >= high {
+            break;
+        }
+        if residual(middle) > T::zero() {
+            low = middle;
+        } else {
+            high = middle;
+        }
+    }
+    step(high)
+}
+
+/// Everything about the iteration that is not the current point.
+struct State<T> {
+    subproblem: CubicSubproblem,
+    regularisation: T,
+}
+
+impl<T: Real> State<T> {
+    /// Minimises the cubic model at the current point, with Hessian `hessian`.
+    fn step(&self, hessian: &Matrix<T>, gradient: &[T]) -> Vec<T> {
+        let (basis, reduced_hessian) = match self.subproblem {
+            CubicSubproblem::Eigen => (Matrix::identity(gradient.len()), hessian.clone()),
+            CubicSubproblem::Lanczos(dimension) => match lanczos(hessian, gradient, dimension) {
+                Some(lanczos) => lanczos,
+                None => return vec![T::zero(); gradient.len()],
+            },
+        };
+
+        let eigen = SymmetricEigen::new(&reduced_hessian);
+        // The gradient in the basis of the eigenvectors.
+        let projected = eigen
+            .vectors()
+            .transpose()
+            .mul_vec(&basis.transpose().mul_vec(gradient));
+        let s = cubic_step(eigen.values(), &projected, self.regularisation);
+        basis.mul_vec(&eigen.vectors().mul_vec(&s))
+    }
+
+    /// Computes the next iterate from `x`, where `value` and `gradient` are the value and gradient
+    /// of the function at `x`.
+    ///
+    /// The step minimising the cubic model is accepted if the function decreases by at least a
+    /// tenth of the decrease of the model, or if that is lost in rounding errors and the gradient
+    /// decreases, and `σ` is halved if it decreases by nine tenths.
+    /// Otherwise, `σ` is doubled and the model minimised again. When `σ` becomes too large for
+    /// any step to be taken, `x` is returned if it is a minimiser to working precision, see
+    /// [`failed_search`], and the iteration fails otherwise.
+    fn advance<O, H>(
+        &mut self,
+        objective: &O,
+        hessian: &H,
+        x: &[T],
+        value: T,
+        gradient: &[T],
+    ) -> Advance<T>
+    where
+        O: Fn(&[T]) -> (T, Vec<T>),
+        H: Fn(&[T]) -> Matrix<T>,
+    {
+        let hessian = hessian(x);
+        if !hessian.is_finite() {
+            return Err(NewtonError::NonFiniteDerivative { at: x.to_vec() });
+        }
+
+        loop {
+            let s = self.step(&hessian, gradient);
+            let size = norm(&s);
+            if size == T::zero() || self.regularisation * T::epsilon() >= T::one() {
+                // Whether any step could have decreased the function is judged along the step of
+                // the least regularised model.
+                let least = State {
+                    subproblem: self.subproblem,
+                    regularisation: T::epsilon(),
+                };
+                return failed_search(x, value, gradient, &least.step(&hessian, gradient));
+            }
+
+            let next: Vec<T> = x.iter().zip(&s).map(|(&x, &s)| x + s).collect();
+            let (next_value, next_gradient) = objective(&next);
+
+            let half = T::from_f64(0.5);
+            let third = T::one() / T::from_f64(3.);
+            let predicted = -(dot(gradient, &s)
+                + half * dot(&s, &hessian.mul_vec(&s))
+                + third * self.regularisation * size * size * size);
+            let ratio = if predicted > T::zero() && next_value.is_finite() {
+                (value - next_value) / predicted
+            } else {
+                T::zero()
+            };
+
+            // Below rounding errors, the decrease of the function says nothing, but that of the
+            // gradient still does.
+            let negligible =
+                predicted <= T::from_f64(10.) * T::epsilon() * (T::one() + value.abs());
+            let rounding = negligible && norm(&next_gradient) < norm(gradient);
+
+            if ratio >= T::from_f64(0.1) || rounding {
+                if ratio >= T::from_f64(0.9) {
+                    let halved = half * self.regularisation;
+                    self.regularisation = if halved > T::epsilon() {
+                        halved
+                    } else {
+                        T::epsilon()
+                    };
+                }
+                return Ok((next, next_value, next_gradient));
+            }
+            self.regularisation = self.regularisation + self.regularisation;
+        }
+    }
+}
+
+/// Runs `dimension` steps of the Lanczos process on `matrix` from `start`, and returns the
+/// orthonormal basis of the Krylov subspace found, as columns, along with the tridiagonal
+/// projection of `matrix` on it.
+///
+/// The basis is reorthogonalised at each step, and the process stops early if the subspace is
+/// invariant. Returns `None` if `start` is zero.
+fn lanczos<T: Real>(
+    matrix: &Matrix<T>,
+    start: &[T],
+    dimension: usize,
+) -> Option<(Matrix<T>, Matrix<T>)> {
+    let scale = norm(start);
+    if scale == T::zero() {
+        return None;
+    }
+
+    let mut basis: Vec<Vec<T>> = vec![start.iter().map(|&s| s / scale).collect()];
+    let mut diagonal = Vec::new();
+    let mut off_diagonal = Vec::new();
+    let dimension = dimension.clamp(1, start.len());
+
+    loop {
+        let current = basis.last().expect("the basis is not empty");
+        let mut w = matrix.mul_vec(current);
+        diagonal.push(dot(current, &w));
+        for q in &basis {
+            let projection = dot(q, &w);
+            for (w, &q) in w.iter_mut().zip(q) {
+                *w = *w - projection * q;
+            }
+        }
+
+        let beta = norm(&w);
+        if basis.len() >= dimension || beta <= T::epsilon() * scale {
+            break;
+        }
+        off_diagonal.push(beta);
+        basis.push(w.iter().map(|&w| w / beta).collect());
+    }
+
+    let m = basis.len();
+    let tridiagonal = Matrix::from_fn(m, m, |row, col| {
+        if row == col {
+            diagonal[row]
+        } else if row == col + 1 {
+            off_diagonal[col]
+        } else if col == row + 1 {
+            off_diagonal[row]
+        } else {
+            T::zero()
+        }
+    });
+    let basis = Matrix::from_fn(start.len(), m, |row, col| basis[col][row]);
+    Some((basis, tridiagonal))
+}
+
+/// An iterator that returns successive iterations of the adaptive regularisation with cubics
+/// method (ARC), for unconstrained minimisation.
+///
+/// Each iteration minimises the cubic model
+///
+/// ```text
+/// f(x) + ∇f(x)ᵀ·s + ½·sᵀ·H(x)·s + σ/3·‖s‖³,
+/// ```
+///
+/// where `H` is the Hessian matrix of `f`, as described by the [`CubicSubproblem`]. The cubic
+/// term keeps the model bounded below even where `H` is indefinite, so that iterations escape
+/// saddle points along directions of negative curvature. The regularisation `σ` is adapted to
+/// how well the model predicts the decrease of the function.
+///
+/// The iterator stops when the Hessian is not finite, or no step decreases the function.
+///
+/// # Example
+///
+/// ```
+/// use generic_newton::{AdaptiveCubic, Matrix};
+///
+/// // A function with a saddle point at the origin and minima at (0, ±1).
+/// let mut n = AdaptiveCubic::new(
+///     vec![0., 0.], // Initial guess, the saddle point
+///     |x: &[f64]| x[0] * x[0] + (x[1] * x[1] - 1.).powi(2), // The function
+///     |x: &[f64]| vec![2. * x[0], 4. * x[1] * (x[1] * x[1] - 1.)], // Its gradient
+///     |x: &[f64]| Matrix::from_rows(&[[2., 0.], [0., 12. * x[1] * x[1] - 4.]]), // Its Hessian
+/// );
+///
+/// let minimiser = n.nth(20).unwrap();
+/// assert!(minimiser[0].abs() < 1E-15 && (minimiser[1].abs() - 1.).abs() < 1E-15);
+/// ```
+pub struct AdaptiveCubic<T, F, G, H>
+where
+    T: Real,
+    F: Fn(&[T]) -> T,
+    G: Fn(&[T]) -> Vec<T>,
+    H: Fn(&[T]) -> Matrix<T>,
+{
+    current: Vec<T>,
+    current_value: Option<(T, Vec<T>)>,
+    func: F,
+    gradient: G,
+    hessian: H,
+    state: State<T>,
+}
+
+impl<T, F, G, H> AdaptiveCubic<T, F, G, H>
+where
+    T: Real,
+    F: Fn(&[T]) -> T,
+    G: Fn(&[T]) -> Vec<T>,
+    H: Fn(&[T]) -> Matrix<T>,
+{
+    /// Creates a new `AdaptiveCubic` iterator, solving the subproblems with
+    /// [`CubicSubproblem::Eigen`], and starting with `σ = 1`.
+    ///
+    /// - `func` is the actual function to minimise
+    /// - `gradient` is it's gradient
+    /// - `hessian` is it's Hessian matrix.
+    pub fn new(initial_guess: Vec<T>, func: F, gradient: G, hessian: H) -> Self {
+        AdaptiveCubic {
+            current: initial_guess,
+            current_value: None,
+            func,
+            gradient,
+            hessian,
+            state: State {
+                subproblem: CubicSubproblem::Eigen,
+                regularisation: T::one(),
+            },
+        }
+    }
+
+    /// Sets how the cubic models are minimised.
+    pub fn with_subproblem(mut self, subproblem: CubicSubproblem) -> Self {
+        self.state.subproblem = subproblem;
+        self
+    }
+
+    /// Sets the initial regularisation `σ`.
+    pub fn with_regularisation(mut self, regularisation: T) -> Self {
+        self.state.regularisation = regularisation;
+        self
+    }
+
+    /// Iterates until `criteria` are met, and returns the minimiser found.
+    ///
+    /// The residual tolerance of `criteria` applies to the norm of the gradient, so that the
+    /// iteration may stop at a saddle point if started there. This is reported by
+    /// [`CubicOptimum::second_order`]. When the regularisation grows too large for any step to
+    /// decrease the function away from a minimiser to working precision, the iteration fails
+    /// with [`NewtonError::LineSearchFailed`].
+    pub fn solve(self, criteria: &Criteria<T>) -> Result<CubicOptimum<T>, NewtonError<Vec<T>>> {
+        let (func, gradient, hessian) = (&self.func, &self.gradient, &self.hessian);
+        let objective = |x: &[T]| (func(x), gradient(x));
+        let mut state = self.state;
+        let cached: Cached<T> = Cell::new(self.current_value);
+        let value = Cell::new(None);
+
+        let Solution {
+            root,
+            residual,
+            iterations,
+        } = solve_system_with(
+            criteria,
+            self.current,
+            |x| {
+                let (fx, gx) = cached.take().unwrap_or_else(|| objective(x));
+                value.set(Some(fx));
+                gx
+            },
+            |x, gx| {
+                let fx = value.get().expect("the residual was computed");
+                let (next, next_value, next_gradient) =
+                    state.advance(&objective, hessian, x, fx, gx)?;
+                cached.set(Some((next_value, next_gradient)));
+                Ok(next)
+            },
+        )?;
+
+        let eigen = SymmetricEigen::new(&hessian(&root));
+        let values = eigen.values();
+        let smallest = values.first().copied().unwrap_or_else(T::zero);
+        let largest = values.iter().fold(
+            T::zero(),
+            |acc, &v| {
+                if v.abs() > acc {
+                    v.abs()
+                } else {
+                    acc
+                }
+            },
+        );
+
+        Ok(CubicOptimum {
+            value: value.get().expect("the residual was computed"),
+            gradient_norm: norm(&residual),
+            minimiser: root,
+            gradient: residual,
+            iterations,
+            regularisation: state.regularisation,
+            smallest_eigenvalue: smallest,
+            second_order: smallest >= -T::epsilon().sqrt() * largest,
+        })
+    }
+}
+
+impl<T, F, G, H> Iterator for AdaptiveCubic<T, F, G, H>
+where
+    T: Real,
+    F: Fn(&[T]) -> T,
+    G: Fn(&[T]) -> Vec<T>,
+    H: Fn(&[T]) -> Matrix<T>,
+{
+    type Item = Vec<T>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let (func, gradient) = (&self.func, &self.gradient);
+        let objective = |x: &[T]| (func(x), gradient(x));
+
+        let (value, grad) = match self.current_value.take() {
+            Some(cached) => cached,
+            None => objective(&self.current),
+        };
+        let (next, next_value, next_gradient) = self
+            .state
+            .advance(&objective, &self.hessian, &self.current, value, &grad)
+            .ok()?;
+
+        self.current = next;
+        self.current_value = Some((next_value, next_gradient));
+        Some(self.current.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{AdaptiveCubic, CubicSubproblem};
+    use crate::{Criteria, Matrix, NewtonError};
+
+    /// A function with a saddle point at the origin and minima at `(±1, 0, 0)`.
+    fn func(x: &[f64]) -> f64 {
+        (x[0] * x[0] - 1.).powi(2) + x[1] * x[1] + 2. * x[2] * x[2] + x[0] * x[2]
+    }
+
+    fn gradient(x: &[f64]) -> Vec<f64> {
+        vec![
+            4. * x[0] * (x[0] * x[0] - 1.) + x[2],
+            2. * x[1],
+            4. * x[2] + x[0],
+        ]
+    }
+
+    fn hessian(x: &[f64]) -> Matrix<f64> {
+        Matrix::from_rows(&[[12. * x[0] * x[0] - 4., 0., 1.], [0., 2., 0.], [1., 0., 4.]])
+    }
+
+    #[test]
+    fn subproblems_escape_saddle() {
+        let criteria = Criteria {
+            residual_tolerance: 1E-12,
+            ..Criteria::default()
+        };
+
+        for &subproblem in &[CubicSubproblem::Eigen, CubicSubproblem::Lanczos(3)] {
+            let optimum = AdaptiveCubic::new(vec![1E-3, 1., 0.], func, gradient, hessian)
+                .with_subproblem(subproblem)
+                .solve(&criteria)
+                .unwrap();
+            assert!(optimum.gradient_norm <= 1E-12);
+            assert!(optimum.second_order);
+            assert!(optimum.smallest_eigenvalue > 0.);
+            assert!(optimum.value < func(&[0., 0., 0.]));
+        }
+    }
+
+    #[test]
+    fn reports_saddle() {
+        // Started exactly at the saddle, `solve` stops at once, but reports it.
+        let optimum = AdaptiveCubic::new(vec![0., 0., 0.], func, gradient, hessian)
+            .solve(&Criteria::default())
+            .unwrap();
+        assert_eq!(optimum.iterations, 0);
+        assert!(!optimum.second_order);
+        assert!(optimum.smallest_eigenvalue < 0.);
+
+        // The curvature is judged relative to the Hessian, however small.
+        let scale = 1E-9;
+        let optimum = AdaptiveCubic::new(
+            vec![0., 0., 0.],
+            |x: &[f64]| scale * func(x),
+            |x: &[f64]| gradient(x).iter().map(|g| scale * g).collect(),
+            |x: &[f64]| {
+                let h = hessian(x);
+                Matrix::from_fn(3, 3, |i, j| scale * h[(i, j)])
+            },
+        )
+        .solve(&Criteria::default())
+        .unwrap();
+        assert!(!optimum.second_order);
+
+        // The exact subproblem finds the direction of negative curvature anyway.
+        let mut iterations = AdaptiveCubic::new(vec![0., 0., 0.], func, gradient, hessian);
+        let x = iterations.nth(30).unwrap();
+        assert!(gradient(&x).iter().all(|g| g.abs() < 1E-12));
+        assert!(func(&x) < 0.);
+    }
+
+    #[test]
+    fn reports_failed_step() {
+        // A gradient of the wrong sign makes every step an ascent one.
+        for &subproblem in &[CubicSubproblem::Eigen, CubicSubproblem::Lanczos(1)] {
+            let result = AdaptiveCubic::new(
+                vec![1.],
+                |x: &[f64]| x[0] * x[0],
+                |x: &[f64]| vec![-2. * x[0]],
+                |_: &[f64]| Matrix::from_rows(&[[2.]]),
+            )
+            .with_subproblem(subproblem)
+            .solve(&Criteria::default());
+            assert_eq!(result, Err(NewtonError::LineSearchFailed { at: vec![1.] }));
+        }
+    }
+}
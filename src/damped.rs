@@ -0,0 +1,243 @@
+use std::cell::Cell;
+use std::iter::Iterator;
+
+use crate::solve::{check_derivative, solve_with};
+use crate::{Criteria, NewtonError, Real, Solution};
+
+/// The rule deciding whether a damped step decreases the residual enough.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LineSearch<T> {
+    /// Accepts any step that decreases `|f|`, or any step from an exact root.
+    Halving,
+    /// Accepts a step damped by `λ` if `|f(x - λ·s)| <= (1 - c·λ)·|f(x)|`, where `c` is the given
+    /// sufficient decrease parameter, between zero and one.
+    Armijo(T),
+}
+
+/// Everything computed during a single damped Newton iteration.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DampedStep<T> {
+    /// The point the step was taken from.
+    pub previous: T,
+    /// The value of the function at `previous`.
+    pub value: T,
+    /// The value of the derivative at `previous`.
+    pub derivative: T,
+    /// The full Newton step `value / derivative`.
+    pub step: T,
+    /// The factor the step was multiplied by.
+    pub damping: T,
+    /// The new iterate, `previous - damping * step`.
+    pub next: T,
+    /// The value of the function at `next`.
+    pub next_value: T,
+}
+
+/// An iterator that returns successive iterations of a damped Newton's method.
+///
+/// Each Newton step is halved until the residual `|f|` decreases as required by the
+/// [`LineSearch`], which prevents overshooting and oscillations on badly-scaled problems.
+///
+/// # Example
+///
+/// ```
+/// use generic_newton::DampedNewton;
+///
+/// let mut n = DampedNewton::new(
+///     10., // Initial guess
+///     |x: f64| x.atan(), // The actual function
+///     |x| 1. / (1. + x * x), // Its derivative
+/// );
+///
+/// let step = n.next().unwrap();
+/// assert!(step.damping < 1.);
+/// assert!(n.nth(10).unwrap().next.abs() < 1E-15)
+/// ```
+pub struct DampedNewton<T, F, DF>
+where
+    T: Real,
+    F: Fn(T) -> T,
+    DF: Fn(T) -> T,
+{
+    current: T,
+    current_value: Option<T>,
+    func: F,
+    derivative: DF,
+    line_search: LineSearch<T>,
+    min_damping: T,
+}
+
+impl<T, F, DF> DampedNewton<T, F, DF>
+where
+    T: Real,
+    F: Fn(T) -> T,
+    DF: Fn(T) -> T,
+{
+    /// Creates a new `DampedNewton` iterator, using an Armijo line search with `c = 1E-4`.
+    ///
+    /// - `func` is the actual function to find the root of
+    /// - `derivative` is it's derivative.
+    pub fn new(initial_guess: T, func: F, derivative: DF) -> Self {
+        DampedNewton {
+            current: initial_guess,
+            current_value: None,
+            func,
+            derivative,
+            line_search: LineSearch::Armijo(T::from_f64(1E-4)),
+            min_damping: T::from_f64(1E-10),
+        }
+    }
+
+    /// Sets the rule used to accept damped steps.
+    pub fn with_line_search(mut self, line_search: LineSearch<T>) -> Self {
+        self.line_search = line_search;
+        self
+    }
+
+    /// Sets the smallest damping factor, which is used when no larger factor is accepted.
+    ///
+    /// # Panics
+    ///
+    /// If `min_damping` is not positive, as the halving would then never stop.
+    pub fn with_min_damping(mut self, min_damping: T) -> Self {
+        let positive = min_damping > T::zero();
+        assert!(positive, "the minimum damping must be positive");
+        self.min_damping = min_damping;
+        self
+    }
+
+    /// Halves the Newton step from `previous` until it is accepted, and returns it along with
+    /// whether it was, or the step damped by the minimum damping otherwise.
+    fn damped_step(&self, previous: T, value: T, derivative: T) -> (DampedStep<T>, bool) {
+        let func = &self.func;
+
+        let step = value / derivative;
+        let half = T::from_f64(0.5);
+        let mut damping = T::one();
+
+        loop {
+            let next = previous - damping * step;
+            let next_value = func(next);
+
+            // A step too small to move the iterate is never accepted, unless already at a root.
+            let accepted = value == T::zero()
+                || next != previous
+                    && match self.line_search {
+                        LineSearch::Halving => next_value.abs() < value.abs(),
+                        LineSearch::Armijo(c) => {
+                            next_value.abs() <= (T::one() - c * damping) * value.abs()
+                        }
+                    };
+
+            if accepted || damping * half < self.min_damping {
+                let step = DampedStep {
+                    previous,
+                    value,
+                    derivative,
+                    step,
+                    damping,
+                    next,
+                    next_value,
+                };
+                return (step, accepted);
+            }
+            damping = damping * half;
+        }
+    }
+
+    /// Iterates until `criteria` are met, and returns the root found.
+    ///
+    /// Fails with [`NewtonError::LineSearchFailed`] if no damping down to the minimum one is
+    /// accepted.
+    pub fn solve(self, criteria: &Criteria<T>) -> Result<Solution<T>, NewtonError<T>> {
+        let func = &self.func;
+        let deriv = &self.derivative;
+        let cached = Cell::new(self.current_value);
+
+        solve_with(
+            criteria,
+            self.current,
+            |x| cached.take().unwrap_or_else(|| func(x)),
+            |x, fx| {
+                let derivative = check_derivative(x, deriv(x))?;
+                let (step, accepted) = self.damped_step(x, fx, derivative);
+                if !accepted {
+                    return Err(NewtonError::LineSearchFailed { at: x });
+                }
+                cached.set(Some(step.next_value));
+                Ok(step.next)
+            },
+        )
+    }
+}
+
+impl<T, F, DF> Iterator for DampedNewton<T, F, DF>
+where
+    T: Real,
+    F: Fn(T) -> T,
+    DF: Fn(T) -> T,
+{
+    type Item = DampedStep<T>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let func = &self.func;
+        let deriv = &self.derivative;
+
+        let value = self.current_value.unwrap_or_else(|| func(self.current));
+        let (step, _) = self.damped_step(self.current, value, deriv(self.current));
+
+        self.current = step.next;
+        self.current_value = Some(step.next_value);
+        Some(step)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{DampedNewton, LineSearch};
+    use crate::{Criteria, Newton, NewtonError};
+
+    #[test]
+    fn damps_overshoot() {
+        let criteria = Criteria::default();
+
+        // Plain Newton diverges from this guess.
+        assert!(Newton::new(3., |x: f64| x.atan(), |x| 1. / (1. + x * x))
+            .solve(&criteria)
+            .is_err());
+
+        for line_search in &[LineSearch::Halving, LineSearch::Armijo(0.5)] {
+            let mut n = DampedNewton::new(3., |x: f64| x.atan(), |x| 1. / (1. + x * x))
+                .with_line_search(*line_search);
+            let steps: Vec<_> = n.by_ref().take(10).collect();
+
+            assert!(steps[0].damping < 1.);
+            for step in &steps {
+                assert!(step.next_value.abs() < step.value.abs() || step.value == 0.);
+                assert_eq!(step.next, step.previous - step.damping * step.step);
+            }
+            assert_eq!(steps.last().unwrap().damping, 1.);
+
+            let solution = DampedNewton::new(3., |x: f64| x.atan(), |x| 1. / (1. + x * x))
+                .with_line_search(*line_search)
+                .solve(&criteria)
+                .unwrap();
+            assert!(solution.root.abs() < 1E-15);
+        }
+    }
+
+    #[test]
+    fn min_damping() {
+        // A derivative with the wrong sign never decreases the residual.
+        let mut n = DampedNewton::new(1., |x: f64| x * x + 1., |x| -2. * x).with_min_damping(0.1);
+        assert_eq!(n.next().unwrap().damping, 0.125);
+
+        for line_search in &[LineSearch::Halving, LineSearch::Armijo(1E-4)] {
+            let result = DampedNewton::new(1., |x: f64| x * x + 1., |x| -2. * x)
+                .with_line_search(*line_search)
+                .with_min_damping(f64::MIN_POSITIVE)
+                .solve(&Criteria::default());
+            assert_eq!(result, Err(NewtonError::LineSearchFailed { at: 1. }));
+        }
+    }
+}
@@ -0,0 +1,357 @@
+use std::cell::Cell;
+use std::iter::Iterator;
+
+use crate::linalg::{dot, norm, LinearSolver, LuSolver, Matrix};
+use crate::solve::solve_system_with;
+use crate::{Criteria, NewtonError, Real, Solution};
+
+/// Everything computed during a single trust-region iteration.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DoglegStep<T> {
+    /// The point the step was taken from.
+    pub previous: Vec<T>,
+    /// The step that was tried.
+    pub step: Vec<T>,
+    /// The new iterate, which is `previous` if the step was rejected.
+    pub next: Vec<T>,
+    /// The ratio between the actual and predicted reductions of `½‖F‖²`.
+    pub ratio: T,
+    /// The trust-region radius the step was bounded by.
+    pub radius: T,
+    /// Whether the step was accepted.
+    pub accepted: bool,
+}
+
+/// The result of an attempted step, along with the value of the function at the new iterate.
+type Attempt<T> = Result<(DoglegStep<T>, Vec<T>), NewtonError<Vec<T>>>;
+
+/// Everything about the iteration that is not the current point.
+struct State<T, S> {
+    solver: S,
+    radius: T,
+    max_radius: T,
+    /// The Jacobian at the current point, and the Gauss–Newton step if it is not singular.
+    local: Option<(Matrix<T>, Option<Vec<T>>)>,
+}
+
+impl<T: Real, S: LinearSolver<T>> State<T, S> {
+    /// Computes the dogleg step from `x`, where `fx` is `func(x)`, and tries it.
+    fn attempt<F, J>(&mut self, func: &F, jacobian: &J, x: &[T], fx: &[T]) -> Attempt<T>
+    where
+        F: Fn(&[T]) -> Vec<T>,
+        J: Fn(&[T]) -> Matrix<T>,
+    {
+        let (jac, gauss_newton) = match self.local.take() {
+            Some(local) => local,
+            None => {
+                let jac = jacobian(x);
+                if !jac.is_finite() {
+                    return Err(NewtonError::NonFiniteDerivative { at: x.to_vec() });
+                }
+                let gauss_newton = self
+                    .solver
+                    .solve(&jac, fx)
+                    .map(|d| d.into_iter().map(|d| -d).collect::<Vec<T>>());
+                (jac, gauss_newton)
+            }
+        };
+
+        let gradient = jac.transpose().mul_vec(fx);
+        let gradient_norm = norm(&gradient);
+        if gradient_norm == T::zero() {
+            return Err(NewtonError::ZeroDerivative { at: x.to_vec() });
+        }
+
+        let radius = self.radius;
+        let step = match &gauss_newton {
+            Some(gn) if norm(gn) <= radius => gn.clone(),
+            _ => {
+                // The minimiser of the model along the steepest descent direction.
+                let jg = jac.mul_vec(&gradient);
+                let alpha = gradient_norm * gradient_norm / dot(&jg, &jg);
+                let cauchy: Vec<T> = gradient.iter().map(|&g| -alpha * g).collect();
+                let cauchy_norm = alpha * gradient_norm;
+
+                match &gauss_newton {
+                    Some(gn) if cauchy_norm < radius => {
+                        // Walks from the Cauchy point towards the Gauss–Newton point until the
+                        // boundary, solving ‖c + τ·(gn - c)‖ = radius for τ in [0, 1].
+                        let d: Vec<T> = gn.iter().zip(&cauchy).map(|(&g, &c)| g - c).collect();
+                        let a = dot(&d, &d);
+                        let b = dot(&cauchy, &d);
+                        let c = cauchy_norm * cauchy_norm - radius * radius;
+                        let tau = (-b + (b * b - a * c).sqrt()) / a;
+                        cauchy.iter().zip(&d).map(|(&c, &d)| c + tau * d).collect()
+                    }
+                    _ => {
+                        let factor = if cauchy_norm < radius {
+                            alpha
+                        } else {
+                            radius / gradient_norm
+                        };
+                        gradient.iter().map(|&g| -factor * g).collect()
+                    }
+                }
+            }
+        };
+
+        let next: Vec<T> = x.iter().zip(&step).map(|(&x, &s)| x + s).collect();
+        let next_value = func(&next);
+
+        let half = T::from_f64(0.5);
+        let current_merit = half * dot(fx, fx);
+        let model: Vec<T> = fx
+            .iter()
+            .zip(jac.mul_vec(&step))
+            .map(|(&f, js)| f + js)
+            .collect();
+        let predicted = current_merit - half * dot(&model, &model);
+        let actual = current_merit - half * dot(&next_value, &next_value);
+        let ratio = if predicted > T::zero() && actual.is_finite() {
+            actual / predicted
+        } else {
+            T::zero()
+        };
+
+        let step_norm = norm(&step);
+        if ratio < T::from_f64(0.25) {
+            self.radius = T::from_f64(0.25) * step_norm;
+        } else if ratio > T::from_f64(0.75) && step_norm >= T::from_f64(0.99) * radius {
+            let doubled = radius + radius;
+            self.radius = if doubled > self.max_radius {
+                self.max_radius
+            } else {
+                doubled
+            };
+        }
+
+        let accepted = ratio > T::from_f64(1E-4);
+        if accepted {
+            Ok((
+                DoglegStep {
+                    previous: x.to_vec(),
+                    step,
+                    next,
+                    ratio,
+                    radius,
+                    accepted,
+                },
+                next_value,
+            ))
+        } else {
+            self.local = Some((jac, gauss_newton));
+            Ok((
+                DoglegStep {
+                    previous: x.to_vec(),
+                    step,
+                    next: x.to_vec(),
+                    ratio,
+                    radius,
+                    accepted,
+                },
+                fx.to_vec(),
+            ))
+        }
+    }
+}
+
+/// An iterator that returns successive iterations of Powell's dogleg trust-region method for
+/// systems of equations.
+///
+/// Steps are restricted to a region where the linear model of the function is trusted. Inside
+/// this region, the step follows a path from the steepest descent step on `½‖F‖²` to the Newton
+/// step. The radius of the region grows or shrinks depending on how well the model predicted the
+/// actual reduction, and steps that do not reduce `‖F‖` are rejected.
+///
+/// The iterator stops when the gradient of `½‖F‖²` vanishes away from a root.
+///
+/// # Example
+///
+/// ```
+/// use generic_newton::{Dogleg, Matrix};
+///
+/// // The intersections of the unit circle and the line y = x.
+/// let mut d = Dogleg::new(
+///     vec![10., 0.], // Initial guess
+///     |x: &[f64]| vec![x[0] * x[0] + x[1] * x[1] - 1., x[0] - x[1]], // The actual function
+///     |x: &[f64]| Matrix::from_rows(&[[2. * x[0], 2. * x[1]], [1., -1.]]), // Its Jacobian
+/// );
+///
+/// let step = d.nth(30).unwrap();
+/// assert!((step.next[0] - 0.5f64.sqrt()).abs() < 1E-15);
+/// assert!((step.next[1] - 0.5f64.sqrt()).abs() < 1E-15);
+/// ```
+pub struct Dogleg<T, F, J, S = LuSolver>
+where
+    T: Real,
+    F: Fn(&[T]) -> Vec<T>,
+    J: Fn(&[T]) -> Matrix<T>,
+    S: LinearSolver<T>,
+{
+    current: Vec<T>,
+    current_value: Option<Vec<T>>,
+    func: F,
+    jacobian: J,
+    state: State<T, S>,
+}
+
+impl<T, F, J> Dogleg<T, F, J>
+where
+    T: Real,
+    F: Fn(&[T]) -> Vec<T>,
+    J: Fn(&[T]) -> Matrix<T>,
+{
+    /// Creates a new `Dogleg` iterator, with an initial trust-region radius of one.
+    ///
+    /// - `func` is the actual function to find the root of
+    /// - `jacobian` is it's Jacobian matrix.
+    pub fn new(initial_guess: Vec<T>, func: F, jacobian: J) -> Self {
+        Dogleg {
+            current: initial_guess,
+            current_value: None,
+            func,
+            jacobian,
+            state: State {
+                solver: LuSolver,
+                radius: T::one(),
+                max_radius: T::from_f64(1E10),
+                local: None,
+            },
+        }
+    }
+}
+
+impl<T, F, J, S> Dogleg<T, F, J, S>
+where
+    T: Real,
+    F: Fn(&[T]) -> Vec<T>,
+    J: Fn(&[T]) -> Matrix<T>,
+    S: LinearSolver<T>,
+{
+    /// Replaces the solver used for the Newton steps.
+    pub fn with_solver<S2: LinearSolver<T>>(self, solver: S2) -> Dogleg<T, F, J, S2> {
+        Dogleg {
+            current: self.current,
+            current_value: self.current_value,
+            func: self.func,
+            jacobian: self.jacobian,
+            state: State {
+                solver,
+                radius: self.state.radius,
+                max_radius: self.state.max_radius,
+                local: None,
+            },
+        }
+    }
+
+    /// Sets the initial trust-region radius.
+    pub fn with_radius(mut self, radius: T) -> Self {
+        self.state.radius = radius;
+        self
+    }
+
+    /// Sets the largest trust-region radius.
+    pub fn with_max_radius(mut self, max_radius: T) -> Self {
+        self.state.max_radius = max_radius;
+        self
+    }
+
+    /// Iterates until `criteria` are met, and returns the root found.
+    ///
+    /// Only accepted steps count as iterations. A vanishing gradient of `½‖F‖²` away from a root,
+    /// or a trust region shrinking to nothing, is reported as [`NewtonError::ZeroDerivative`].
+    pub fn solve(self, criteria: &Criteria<T>) -> Result<Solution<Vec<T>>, NewtonError<Vec<T>>> {
+        let func = &self.func;
+        let jacobian = &self.jacobian;
+        let mut state = self.state;
+        let cached = Cell::new(self.current_value);
+
+        solve_system_with(
+            criteria,
+            self.current,
+            |x| cached.take().unwrap_or_else(|| func(x)),
+            |x, fx| loop {
+                let (step, next_value) = state.attempt(func, jacobian, x, fx)?;
+                if step.accepted {
+                    cached.set(Some(next_value));
+                    return Ok(step.next);
+                }
+                if state.radius <= T::epsilon() * norm(x) || state.radius == T::zero() {
+                    return Err(NewtonError::ZeroDerivative { at: x.to_vec() });
+                }
+            },
+        )
+    }
+}
+
+impl<T, F, J, S> Iterator for Dogleg<T, F, J, S>
+where
+    T: Real,
+    F: Fn(&[T]) -> Vec<T>,
+    J: Fn(&[T]) -> Matrix<T>,
+    S: LinearSolver<T>,
+{
+    type Item = DoglegStep<T>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let func = &self.func;
+
+        let value = match self.current_value.take() {
+            Some(value) => value,
+            None => func(&self.current),
+        };
+        let (step, next_value) = self
+            .state
+            .attempt(func, &self.jacobian, &self.current, &value)
+            .ok()?;
+
+        self.current = step.next.clone();
+        self.current_value = Some(next_value);
+        Some(step)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::Dogleg;
+    use crate::{Criteria, Matrix};
+
+    /// Powell's badly scaled function.
+    fn func(x: &[f64]) -> Vec<f64> {
+        vec![
+            1E4 * x[0] * x[1] - 1.,
+            (-x[0]).exp() + (-x[1]).exp() - 1.0001,
+        ]
+    }
+
+    fn jacobian(x: &[f64]) -> Matrix<f64> {
+        Matrix::from_rows(&[[1E4 * x[1], 1E4 * x[0]], [-(-x[0]).exp(), -(-x[1]).exp()]])
+    }
+
+    #[test]
+    fn badly_scaled() {
+        let criteria = Criteria {
+            max_iterations: 500,
+            ..Criteria::default()
+        };
+
+        let solution = Dogleg::new(vec![0., 1.], func, jacobian)
+            .solve(&criteria)
+            .unwrap();
+        assert!(solution.residual.iter().all(|r| r.abs() < 1E-10));
+        assert!((solution.root[0] - 1.098159E-5).abs() < 1E-10);
+    }
+
+    #[test]
+    fn reports_ratio() {
+        let mut d = Dogleg::new(vec![0., 1.], func, jacobian).with_radius(100.);
+        let mut rejected = false;
+
+        for step in d.by_ref().take(100) {
+            assert!(step.accepted == (step.ratio > 1E-4));
+            assert!(step.step.iter().map(|s| s * s).sum::<f64>().sqrt() <= step.radius * 1.0001);
+            rejected |= !step.accepted;
+        }
+        assert!(rejected);
+    }
+}
@@ -0,0 +1,347 @@
+use std::cell::Cell;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+use std::rc::Rc;
+
+use crate::{Newton, Real};
+
+/// A dual number `value + derivative·ε`, with `ε² = 0`.
+///
+/// Evaluating a function on `Dual::variable(x)` yields both `f(x)` and `f'(x)`, which is
+/// forward-mode automatic differentiation. Functions written once over any [`Real`] can then be
+/// used both for values and derivatives.
+///
+/// # Example
+///
+/// ```
+/// use generic_newton::{Dual, Real};
+///
+/// fn f<T: Real>(x: T) -> T {
+///     x.cos() - x.powi(3)
+/// }
+///
+/// let y = f(Dual::variable(0.5f64));
+///
+/// assert_eq!(y.value, f(0.5));
+/// assert_eq!(y.derivative, -(0.5f64.sin() + 3. * 0.5f64.powi(2)));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Dual<T> {
+    /// The value of the number.
+    pub value: T,
+    /// The derivative carried along with the value.
+    pub derivative: T,
+}
+
+impl<T> Dual<T> {
+    /// Creates a new dual number.
+    pub fn new(value: T, derivative: T) -> Self {
+        Dual { value, derivative }
+    }
+}
+
+impl<T: Real> Dual<T> {
+    /// Creates a dual number whose derivative is zero.
+    pub fn constant(value: T) -> Self {
+        Dual::new(value, T::zero())
+    }
+
+    /// Creates a dual number whose derivative is one, to differentiate with respect to it.
+    pub fn variable(value: T) -> Self {
+        Dual::new(value, T::one())
+    }
+
+    /// Applies a function whose value is `value` and derivative is `derivative` at `self.value`,
+    /// following the chain rule.
+    ///
+    /// The derivative of a constant stays zero, even where `derivative` is infinite.
+    fn chain(self, value: T, derivative: T) -> Self {
+        if self.derivative == T::zero() {
+            return Dual::constant(value);
+        }
+        Dual::new(value, derivative * self.derivative)
+    }
+}
+
+impl<T: Add<Output = T>> Add for Dual<T> {
+    type Output = Self;
+    fn add(self, other: Self) -> Self {
+        Dual::new(self.value + other.value, self.derivative + other.derivative)
+    }
+}
+
+impl<T: Add<Output = T>> Add<T> for Dual<T> {
+    type Output = Self;
+    fn add(self, other: T) -> Self {
+        Dual::new(self.value + other, self.derivative)
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Dual<T> {
+    type Output = Self;
+    fn sub(self, other: Self) -> Self {
+        Dual::new(self.value - other.value, self.derivative - other.derivative)
+    }
+}
+
+impl<T: Sub<Output = T>> Sub<T> for Dual<T> {
+    type Output = Self;
+    fn sub(self, other: T) -> Self {
+        Dual::new(self.value - other, self.derivative)
+    }
+}
+
+impl<T: Add<Output = T> + Mul<Output = T> + Copy> Mul for Dual<T> {
+    type Output = Self;
+    fn mul(self, other: Self) -> Self {
+        Dual::new(
+            self.value * other.value,
+            self.derivative * other.value + self.value * other.derivative,
+        )
+    }
+}
+
+impl<T: Mul<Output = T> + Copy> Mul<T> for Dual<T> {
+    type Output = Self;
+    fn mul(self, other: T) -> Self {
+        Dual::new(self.value * other, self.derivative * other)
+    }
+}
+
+impl<T: Sub<Output = T> + Mul<Output = T> + Div<Output = T> + Copy> Div for Dual<T> {
+    type Output = Self;
+    fn div(self, other: Self) -> Self {
+        Dual::new(
+            self.value / other.value,
+            (self.derivative * other.value - self.value * other.derivative)
+                / (other.value * other.value),
+        )
+    }
+}
+
+impl<T: Div<Output = T> + Copy> Div<T> for Dual<T> {
+    type Output = Self;
+    fn div(self, other: T) -> Self {
+        Dual::new(self.value / other, self.derivative / other)
+    }
+}
+
+impl<T: Neg<Output = T>> Neg for Dual<T> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Dual::new(-self.value, -self.derivative)
+    }
+}
+
+impl<T: Real> Real for Dual<T> {
+    fn zero() -> Self {
+        Dual::constant(T::zero())
+    }
+
+    fn one() -> Self {
+        Dual::constant(T::one())
+    }
+
+    fn epsilon() -> Self {
+        Dual::constant(T::epsilon())
+    }
+
+    fn from_f64(value: f64) -> Self {
+        Dual::constant(T::from_f64(value))
+    }
+
+    fn abs(self) -> Self {
+        if self.value < T::zero() {
+            -self
+        } else {
+            self
+        }
+    }
+
+    fn is_finite(self) -> bool {
+        self.value.is_finite() && self.derivative.is_finite()
+    }
+
+    fn is_nan(self) -> bool {
+        self.value.is_nan() || self.derivative.is_nan()
+    }
+
+    fn sqrt(self) -> Self {
+        let root = self.value.sqrt();
+        self.chain(root, T::one() / (root + root))
+    }
+
+    fn powi(self, n: i32) -> Self {
+        if n == 0 {
+            // `n·x^(n-1)` would be `0·∞` at zero.
+            return Dual::constant(T::one());
+        }
+        self.chain(
+            self.value.powi(n),
+            T::from_f64(n.into()) * self.value.powi(n - 1),
+        )
+    }
+
+    fn powf(self, n: Self) -> Self {
+        let power = self.value.powf(n.value);
+        let base_derivative = if self.derivative == T::zero() {
+            T::zero()
+        } else {
+            n.value * self.value.powf(n.value - T::one()) * self.derivative
+        };
+        let exponent_derivative = if n.derivative == T::zero() {
+            T::zero()
+        } else {
+            power * self.value.ln() * n.derivative
+        };
+        Dual::new(power, base_derivative + exponent_derivative)
+    }
+
+    fn exp(self) -> Self {
+        let exp = self.value.exp();
+        self.chain(exp, exp)
+    }
+
+    fn ln(self) -> Self {
+        self.chain(self.value.ln(), T::one() / self.value)
+    }
+
+    fn sin(self) -> Self {
+        self.chain(self.value.sin(), self.value.cos())
+    }
+
+    fn cos(self) -> Self {
+        self.chain(self.value.cos(), -self.value.sin())
+    }
+}
+
+impl<T: Real> Newton<T, fn(T) -> T, fn(T) -> T> {
+    /// Creates a new `Newton` iterator, whose derivative is computed by automatic
+    /// differentiation.
+    ///
+    /// - `func` is the actual function to find the root of, evaluated on [`Dual`] numbers.
+    ///
+    /// `func` is evaluated once per step, as the derivative computed along with the value is
+    /// kept until the derivative is asked for at the same point.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use generic_newton::{Newton, Real};
+    ///
+    /// fn f<T: Real>(x: T) -> T {
+    ///     x.cos() - x.powi(3)
+    /// }
+    ///
+    /// let mut n = Newton::with_autodiff(0.5, f);
+    ///
+    /// assert!((n.nth(1000).unwrap() - 0.865474033102).abs() < 1E-11)
+    /// ```
+    #[allow(clippy::type_complexity)]
+    pub fn with_autodiff<G>(
+        initial_guess: T,
+        func: G,
+    ) -> Newton<T, impl Fn(T) -> T, impl Fn(T) -> T>
+    where
+        G: Fn(Dual<T>) -> Dual<T>,
+    {
+        let func = Rc::new(func);
+        let deriv = Rc::clone(&func);
+        let cache: Rc<Cell<Option<(T, T)>>> = Rc::new(Cell::new(None));
+        let cached = Rc::clone(&cache);
+
+        Newton::new(
+            initial_guess,
+            move |x| {
+                let y = func(Dual::variable(x));
+                cache.set(Some((x, y.derivative)));
+                y.value
+            },
+            move |x| match cached.get() {
+                Some((at, derivative)) if at == x => derivative,
+                _ => deriv(Dual::variable(x)).derivative,
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::cell::Cell;
+
+    use super::Dual;
+    use crate::{Criteria, Newton, Real};
+
+    fn assert_close(a: f64, b: f64) {
+        assert!((a - b).abs() < 1E-12, "{} != {}", a, b);
+    }
+
+    #[test]
+    fn elementary_derivatives() {
+        let x = Dual::variable(0.7f64);
+
+        assert_close(x.sin().derivative, 0.7f64.cos());
+        assert_close(x.cos().derivative, -0.7f64.sin());
+        assert_close(x.exp().derivative, 0.7f64.exp());
+        assert_close(x.ln().derivative, 1. / 0.7);
+        assert_close(x.sqrt().derivative, 0.5 / 0.7f64.sqrt());
+        assert_close(x.powi(3).derivative, 3. * 0.7f64.powi(2));
+        assert_eq!(Dual::variable(0f64).powi(0), Dual::constant(1.));
+        // Constants stay constant where the derivative of the function is infinite.
+        assert_eq!(Dual::constant(0f64).sqrt(), Dual::constant(0.));
+        assert_eq!(Dual::constant(0f64).ln(), Dual::constant(f64::NEG_INFINITY));
+        assert_eq!(
+            Dual::constant(0f64).powf(Dual::constant(0.5)),
+            Dual::constant(0.)
+        );
+        assert_close(
+            x.powf(Dual::constant(2.5)).derivative,
+            2.5 * 0.7f64.powf(1.5),
+        );
+        assert_close(
+            Dual::constant(2f64).powf(x).derivative,
+            2f64.powf(0.7) * 2f64.ln(),
+        );
+        assert_close((-x).abs().derivative, 1.);
+        assert_close((x * x / (x + 1.)).derivative, (0.49 + 1.4) / 1.7f64.powi(2));
+        assert_close((x * 2. - x / 4.).derivative, 1.75);
+    }
+
+    #[test]
+    fn operators_are_generic() {
+        let x = Dual::new(3, 1);
+        let y = x * x - Dual::new(1, 0);
+        assert_eq!(y, Dual::new(8, 6));
+    }
+
+    #[test]
+    fn autodiff_matches_newton() {
+        fn f<T: Real>(x: T) -> T {
+            x.exp() - T::from_f64(2.) * x.sin() - T::from_f64(2.)
+        }
+
+        let manual: Vec<f64> = Newton::new(1., f, |x: f64| x.exp() - 2. * x.cos())
+            .take(5)
+            .collect();
+        let automatic: Vec<f64> = Newton::with_autodiff(1., f).take(5).collect();
+        for (m, a) in manual.iter().zip(&automatic) {
+            assert_close(*m, *a);
+        }
+
+        let solution = Newton::with_autodiff(1., f)
+            .solve(&Criteria::default())
+            .unwrap();
+        assert!(solution.residual.abs() < 1E-12);
+
+        // The value and the derivative come from a single evaluation.
+        let evaluations = Cell::new(0);
+        let counted = |x: Dual<f64>| {
+            evaluations.set(evaluations.get() + 1);
+            f(x)
+        };
+        let solution = Newton::with_autodiff(1., counted)
+            .solve(&Criteria::default())
+            .unwrap();
+        assert_eq!(evaluations.get(), solution.iterations + 1);
+    }
+}
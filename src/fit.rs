@@ -0,0 +1,326 @@
+use crate::linalg::{Matrix, Qr};
+use crate::system::finite_difference_jacobian;
+use crate::{Criteria, LeastSquares, LeastSquaresMethod, NewtonError, Real};
+
+/// The result of fitting a model to data with [`CurveFit`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fit<T> {
+    /// The fitted parameters.
+    pub parameters: Vec<T>,
+    /// The covariance matrix of the parameters, estimated as `(Jᵀ·J)⁻¹·σ²`.
+    ///
+    /// With as many points as parameters, `σ²` cannot be estimated and every entry is infinite.
+    pub covariance: Matrix<T>,
+    /// The standard error of each parameter, which is the square root of its variance.
+    pub standard_errors: Vec<T>,
+    /// The weighted sum of the squares of the residuals.
+    pub sum_of_squares: T,
+    /// The coefficient of determination, the fraction of the variance of the data explained by
+    /// the model.
+    ///
+    /// When the data has no variance, this is `1` if the model fits it exactly and `0` otherwise.
+    pub r_squared: T,
+    /// The number of points minus the number of parameters.
+    pub degrees_of_freedom: usize,
+    /// The number of iterations performed.
+    pub iterations: usize,
+}
+
+impl<T: Real> Fit<T> {
+    /// The intervals `parameter ± quantile · standard_error` for each parameter.
+    ///
+    /// `quantile` is usually taken from Student's t-distribution with
+    /// [`Fit::degrees_of_freedom`] degrees of freedom, for instance `2.228` for a 95% confidence
+    /// level and 10 degrees of freedom.
+    pub fn confidence_intervals(&self, quantile: T) -> Vec<(T, T)> {
+        self.parameters
+            .iter()
+            .zip(&self.standard_errors)
+            .map(|(&p, &e)| (p - quantile * e, p + quantile * e))
+            .collect()
+    }
+}
+
+/// Fits the parameters of a model `y = model(x, parameters)` to data points.
+///
+/// The weighted sum of squares `Σ wᵢ·(model(xᵢ, p) - yᵢ)²` is minimised by [`LeastSquares`], with
+/// a Jacobian approximated by finite differences. The variance of the data `σ²` is estimated from
+/// the residuals, so the weights only need to be proportional to the inverses of the variances of
+/// the points.
+///
+/// # Example
+///
+/// ```
+/// use generic_newton::{Criteria, CurveFit};
+///
+/// let xs = [0., 1., 2., 3., 4., 5.];
+/// let ys = [1.02, 1.48, 2.05, 2.47, 3.01, 3.52];
+///
+/// let fit = CurveFit::new(|x: f64, p: &[f64]| p[0] + p[1] * x, &xs, &ys, vec![0., 0.])
+///     .solve(&Criteria::default())
+///     .unwrap();
+///
+/// assert!((fit.parameters[1] - 0.5).abs() < 2. * fit.standard_errors[1]);
+/// assert!(fit.r_squared > 0.99);
+/// ```
+pub struct CurveFit<'a, T, M>
+where
+    T: Real,
+    M: Fn(T, &[T]) -> T,
+{
+    model: M,
+    xs: &'a [T],
+    ys: &'a [T],
+    weights: Option<&'a [T]>,
+    initial_guess: Vec<T>,
+    method: Option<LeastSquaresMethod<T>>,
+}
+
+impl<'a, T, M> CurveFit<'a, T, M>
+where
+    T: Real,
+    M: Fn(T, &[T]) -> T,
+{
+    /// Creates a new `CurveFit`, where every point has the same weight.
+    pub fn new(model: M, xs: &'a [T], ys: &'a [T], initial_guess: Vec<T>) -> Self {
+        CurveFit {
+            model,
+            xs,
+            ys,
+            weights: None,
+            initial_guess,
+            method: None,
+        }
+    }
+
+    /// Sets the weight of each point.
+    pub fn with_weights(mut self, weights: &'a [T]) -> Self {
+        self.weights = Some(weights);
+        self
+    }
+
+    /// Sets the method used by the underlying [`LeastSquares`] solver.
+    pub fn with_method(mut self, method: LeastSquaresMethod<T>) -> Self {
+        self.method = Some(method);
+        self
+    }
+
+    /// Fits the model until `criteria` are met.
+    ///
+    /// A rank-deficient Jacobian at the fitted parameters, for which the covariance is undefined,
+    /// is reported as [`NewtonError::ZeroDerivative`]. Fewer points than parameters, or a number
+    /// of `ys` or weights different from the number of `xs`, are reported as
+    /// [`NewtonError::DimensionMismatch`].
+    pub fn solve(self, criteria: &Criteria<T>) -> Result<Fit<T>, NewtonError<Vec<T>>> {
+        let CurveFit {
+            model,
+            xs,
+            ys,
+            weights,
+            initial_guess,
+            method,
+        } = self;
+
+        for found in std::iter::once(ys.len()).chain(weights.map(<[T]>::len)) {
+            if found != xs.len() {
+                return Err(NewtonError::DimensionMismatch {
+                    expected: xs.len(),
+                    found,
+                });
+            }
+        }
+        if xs.len() < initial_guess.len() {
+            return Err(NewtonError::DimensionMismatch {
+                expected: initial_guess.len(),
+                found: xs.len(),
+            });
+        }
+
+        let weight = |i: usize| weights.map_or(T::one(), |w| w[i]);
+        let roots: Vec<T> = (0..xs.len()).map(|i| weight(i).sqrt()).collect();
+
+        let residuals = |p: &[T]| -> Vec<T> {
+            xs.iter()
+                .zip(ys)
+                .zip(&roots)
+                .map(|((&x, &y), &root)| root * (model(x, p) - y))
+                .collect()
+        };
+        let step = T::epsilon().sqrt();
+        let jacobian = |p: &[T]| finite_difference_jacobian(&residuals, p, &residuals(p), step);
+
+        let mut solver = LeastSquares::new(initial_guess, &residuals, &jacobian);
+        if let Some(method) = method {
+            solver = solver.with_method(method);
+        }
+        let solution = solver.solve(criteria)?;
+
+        let covariance = Qr::new(&jacobian(&solution.parameters))
+            .normal_inverse()
+            .ok_or_else(|| NewtonError::ZeroDerivative {
+                at: solution.parameters.clone(),
+            })?;
+        let degrees_of_freedom = xs.len() - solution.parameters.len();
+        let n = covariance.cols();
+        let covariance = if degrees_of_freedom == 0 {
+            // The model interpolates the points, which says nothing about their variance.
+            Matrix::from_fn(n, n, |_, _| T::from_f64(f64::INFINITY))
+        } else {
+            let variance = solution.sum_of_squares / T::from_f64(degrees_of_freedom as f64);
+            Matrix::from_fn(n, n, |row, col| covariance[(row, col)] * variance)
+        };
+        let standard_errors = (0..n).map(|i| covariance[(i, i)].sqrt()).collect();
+
+        let total_weight = (0..ys.len()).fold(T::zero(), |acc, i| acc + weight(i));
+        let mean = (0..ys.len()).fold(T::zero(), |acc, i| acc + weight(i) * ys[i]) / total_weight;
+        let total = (0..ys.len()).fold(T::zero(), |acc, i| {
+            acc + weight(i) * (ys[i] - mean) * (ys[i] - mean)
+        });
+
+        let r_squared = if total > T::zero() {
+            T::one() - solution.sum_of_squares / total
+        } else if solution.sum_of_squares == T::zero() {
+            T::one()
+        } else {
+            T::zero()
+        };
+
+        Ok(Fit {
+            r_squared,
+            parameters: solution.parameters,
+            covariance,
+            standard_errors,
+            sum_of_squares: solution.sum_of_squares,
+            degrees_of_freedom,
+            iterations: solution.iterations,
+        })
+    }
+}
+
+/// Fits the parameters of `model` to the points `(xs, ys)`, starting from `initial_guess`, with
+/// the default [`Criteria`].
+///
+/// This is a shorthand for [`CurveFit`], which also allows to weight the points.
+///
+/// # Example
+///
+/// ```
+/// use generic_newton::curve_fit;
+///
+/// let xs = [0., 1., 2., 3., 4., 5., 6.];
+/// let ys: Vec<f64> = xs.iter().map(|x: &f64| 3. * (-0.4 * x).exp()).collect();
+///
+/// let fit = curve_fit(|x, p| p[0] * (p[1] * x).exp(), &xs, &ys, vec![1., 0.]).unwrap();
+///
+/// assert!((fit.parameters[0] - 3.).abs() < 1E-8);
+/// assert!((fit.parameters[1] + 0.4).abs() < 1E-8);
+/// ```
+pub fn curve_fit<T, M>(
+    model: M,
+    xs: &[T],
+    ys: &[T],
+    initial_guess: Vec<T>,
+) -> Result<Fit<T>, NewtonError<Vec<T>>>
+where
+    T: Real,
+    M: Fn(T, &[T]) -> T,
+{
+    CurveFit::new(model, xs, ys, initial_guess).solve(&Criteria::default())
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{curve_fit, CurveFit};
+    use crate::{Criteria, NewtonError};
+
+    const XS: [f64; 6] = [0., 1., 2., 3., 4., 5.];
+    const YS: [f64; 6] = [1.1, 2.8, 5.3, 6.9, 9.2, 10.8];
+
+    #[test]
+    fn matches_linear_regression() {
+        let fit = curve_fit(|x, p| p[0] + p[1] * x, &XS, &YS, vec![0., 0.]).unwrap();
+
+        // The closed form of simple linear regression.
+        let n = XS.len() as f64;
+        let mean_x = XS.iter().sum::<f64>() / n;
+        let mean_y = YS.iter().sum::<f64>() / n;
+        let sxx: f64 = XS.iter().map(|x| (x - mean_x).powi(2)).sum();
+        let sxy: f64 = XS
+            .iter()
+            .zip(&YS)
+            .map(|(x, y)| (x - mean_x) * (y - mean_y))
+            .sum();
+        let slope = sxy / sxx;
+        let intercept = mean_y - slope * mean_x;
+        let syy: f64 = YS.iter().map(|y| (y - mean_y).powi(2)).sum();
+        let sum_of_squares = syy - slope * sxy;
+        let variance = sum_of_squares / (n - 2.);
+
+        assert!((fit.parameters[0] - intercept).abs() < 1E-7);
+        assert!((fit.parameters[1] - slope).abs() < 1E-7);
+        assert!((fit.sum_of_squares - sum_of_squares).abs() < 1E-9);
+        assert!((fit.standard_errors[1] - (variance / sxx).sqrt()).abs() < 1E-6);
+        assert!((fit.covariance[(0, 1)] + mean_x * variance / sxx).abs() < 1E-6);
+        assert!((fit.r_squared - (1. - sum_of_squares / syy)).abs() < 1E-9);
+        assert_eq!(fit.degrees_of_freedom, 4);
+
+        let intervals = fit.confidence_intervals(2.776);
+        assert!(intervals[1].0 < slope && slope < intervals[1].1);
+    }
+
+    #[test]
+    fn weights_duplicate_points() {
+        let model = |x: f64, p: &[f64]| p[0] * x * x + p[1];
+        let weights = [1., 2., 1., 1., 3., 1.];
+        let weighted = CurveFit::new(model, &XS, &YS, vec![1., 1.])
+            .with_weights(&weights)
+            .solve(&Criteria::default())
+            .unwrap();
+
+        let (mut xs, mut ys) = (Vec::new(), Vec::new());
+        for ((&x, &y), &w) in XS.iter().zip(&YS).zip(&weights) {
+            for _ in 0..w as usize {
+                xs.push(x);
+                ys.push(y);
+            }
+        }
+        let duplicated = curve_fit(model, &xs, &ys, vec![1., 1.]).unwrap();
+
+        for (a, b) in weighted.parameters.iter().zip(&duplicated.parameters) {
+            assert!((a - b).abs() < 1E-8);
+        }
+        assert!((weighted.sum_of_squares - duplicated.sum_of_squares).abs() < 1E-8);
+        assert!((weighted.r_squared - duplicated.r_squared).abs() < 1E-8);
+    }
+
+    #[test]
+    fn degenerate_data() {
+        let line = |x: f64, p: &[f64]| p[0] + p[1] * x;
+
+        // An exact interpolation leaves no degree of freedom to estimate the variance from.
+        let fit = curve_fit(line, &[0., 1.], &[1., 3.], vec![0., 0.]).unwrap();
+        assert_eq!(fit.degrees_of_freedom, 0);
+        assert!(fit.standard_errors.iter().all(|&e| e == f64::INFINITY));
+        assert_eq!(fit.covariance[(0, 1)], f64::INFINITY);
+
+        let fit = curve_fit(line, &[0., 1., 2.], &[2., 2., 2.], vec![0., 0.]).unwrap();
+        assert_eq!(fit.r_squared, 1.);
+
+        let quadratic = |x: f64, p: &[f64]| p[0] + p[1] * x + p[2] * x * x;
+        assert_eq!(
+            curve_fit(quadratic, &[0., 1.], &[1., 3.], vec![0., 0., 0.]),
+            Err(NewtonError::DimensionMismatch {
+                expected: 3,
+                found: 2
+            })
+        );
+        assert_eq!(
+            curve_fit(line, &[0., 1., 2.], &[1., 3.], vec![0., 0.]),
+            Err(NewtonError::DimensionMismatch {
+                expected: 3,
+                found: 2
+            })
+        );
+    }
+}
@@ -0,0 +1,326 @@
+use std::io::{self, Write};
+
+use crate::{Complex, Newton, Real};
+
+/// The outcome of running Newton's method from a single pixel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pixel {
+    /// The index of the root reached in [`Basins::roots`], or `None` if the iteration did not
+    /// converge.
+    pub root: Option<usize>,
+    /// The number of iterations taken.
+    pub iterations: usize,
+}
+
+/// Renders the basins of attraction of Newton's method in a rectangle of the complex plane.
+///
+/// Newton's method is started from the centre of every pixel, and the pixel is classified by
+/// the root it converges to. Roots are either given with [`with_roots`](Self::with_roots), or
+/// discovered as the iterations reach them.
+///
+/// # Example
+///
+/// ```
+/// use generic_newton::{Complex, NewtonFractal};
+///
+/// let basins = NewtonFractal::new(
+///     |z: Complex<f64>| z * z * z - 1.,
+///     |z| z * z * 3.,
+///     Complex::new(-2., -2.),
+///     Complex::new(2., 2.),
+///     64,
+///     64,
+/// )
+/// .render();
+///
+/// assert_eq!(basins.roots().len(), 3);
+///
+/// let mut image = Vec::new();
+/// basins.write_ppm(&mut image).unwrap();
+/// assert!(image.starts_with(b"P6\n64 64\n255\n"));
+/// ```
+pub struct NewtonFractal<T, F, DF> {
+    func: F,
+    derivative: DF,
+    lower: Complex<T>,
+    upper: Complex<T>,
+    width: usize,
+    height: usize,
+    max_iterations: usize,
+    tolerance: T,
+    roots: Vec<Complex<T>>,
+}
+
+impl<T, F, DF> NewtonFractal<T, F, DF>
+where
+    T: Real,
+    F: Fn(Complex<T>) -> Complex<T>,
+    DF: Fn(Complex<T>) -> Complex<T>,
+{
+    /// Creates a new renderer.
+    ///
+    /// - `func` is the function whose roots are sought, and `derivative` its derivative.
+    /// - `lower` and `upper` are the bottom-left and top-right corners of the region.
+    /// - `width` and `height` are the resolution of the images, in pixels.
+    pub fn new(
+        func: F,
+        derivative: DF,
+        lower: Complex<T>,
+        upper: Complex<T>,
+        width: usize,
+        height: usize,
+    ) -> Self {
+        NewtonFractal {
+            func,
+            derivative,
+            lower,
+            upper,
+            width,
+            height,
+            max_iterations: 50,
+            tolerance: T::from_f64(1E-10),
+            roots: Vec::new(),
+        }
+    }
+
+    /// Sets the number of iterations after which a pixel is deemed not to converge.
+    ///
+    /// Defaults to 50.
+    pub fn with_max_iterations(mut self, max_iterations: usize) -> Self {
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    /// Sets the tolerance on the Newton step, relative to the modulus of the iterate when it is
+    /// larger than one.
+    ///
+    /// Converged points within the square root of this tolerance are the same root. Defaults
+    /// to `1E-10`.
+    pub fn with_tolerance(mut self, tolerance: T) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Sets the known roots, which fixes their colours across renders.
+    ///
+    /// Roots that are reached but not in this list are still discovered and appended.
+    pub fn with_roots(mut self, roots: Vec<Complex<T>>) -> Self {
+        self.roots = roots;
+        self
+    }
+
+    /// Runs Newton's method from every pixel, row by row from the top.
+    pub fn render(self) -> Basins<T> {
+        let mut roots = self.roots.clone();
+        let span = self.upper - self.lower;
+        let half = T::from_f64(0.5);
+
+        let mut pixels = Vec::with_capacity(self.width * self.height);
+        for row in 0..self.height {
+            let im = self.upper.im
+                - span.im * (T::from_f64(row as f64) + half) / T::from_f64(self.height as f64);
+            for col in 0..self.width {
+                let re = self.lower.re
+                    + span.re * (T::from_f64(col as f64) + half) / T::from_f64(self.width as f64);
+                pixels.push(self.pixel(Complex::new(re, im), &mut roots));
+            }
+        }
+
+        Basins {
+            width: self.width,
+            height: self.height,
+            max_iterations: self.max_iterations,
+            roots,
+            pixels,
+        }
+    }
+
+    fn pixel(&self, start: Complex<T>, roots: &mut Vec<Complex<T>>) -> Pixel {
+        let mut newton = Newton::new(start, &self.func, &self.derivative);
+        for (iteration, step) in newton.steps().take(self.max_iterations).enumerate() {
+            if !step.next.is_finite() {
+                break;
+            }
+            let scale = step.next.norm();
+            let scale = if scale < T::one() { T::one() } else { scale };
+            if step.step.norm() <= self.tolerance * scale {
+                return Pixel {
+                    root: Some(classify(roots, step.next, self.tolerance.sqrt() * scale)),
+                    iterations: iteration + 1,
+                };
+            }
+        }
+        Pixel {
+            root: None,
+            iterations: self.max_iterations,
+        }
+    }
+}
+
+/// Returns the index of the root within `separation` of `point`, adding it if there is none.
+fn classify<T: Real>(roots: &mut Vec<Complex<T>>, point: Complex<T>, separation: T) -> usize {
+    match roots
+        .iter()
+        .position(|&root| (root - point).norm() <= separation)
+    {
+        Some(index) => index,
+        None => {
+            roots.push(point);
+            roots.len() - 1
+        }
+    }
+}
+
+/// The basins of attraction rendered by a [`NewtonFractal`].
+#[derive(Debug, Clone)]
+pub struct Basins<T> {
+    width: usize,
+    height: usize,
+    max_iterations: usize,
+    roots: Vec<Complex<T>>,
+    pixels: Vec<Pixel>,
+}
+
+impl<T> Basins<T> {
+    /// The width of the image, in pixels.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// The height of the image, in pixels.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The roots reached, in the order of their indices.
+    pub fn roots(&self) -> &[Complex<T>] {
+        &self.roots
+    }
+
+    /// The outcome at column `col` and row `row`, with the first row at the top.
+    ///
+    /// # Panics
+    ///
+    /// If the pixel is outside the image.
+    pub fn pixel(&self, col: usize, row: usize) -> Pixel {
+        assert!(col < self.width && row < self.height, "pixel out of bounds");
+        self.pixels[row * self.width + col]
+    }
+
+    /// Writes a binary PPM image, with one colour per root darkened by the iteration count, and
+    /// black where the iteration did not converge.
+    pub fn write_ppm<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        write!(writer, "P6\n{} {}\n255\n", self.width, self.height)?;
+        let mut bytes = Vec::with_capacity(3 * self.pixels.len());
+        for pixel in &self.pixels {
+            match pixel.root {
+                Some(root) => {
+                    let shade = 0.25 + 0.75 * self.speed(pixel);
+                    bytes.extend(
+                        colour(root)
+                            .iter()
+                            .map(|&c| (c * shade * 255.).round() as u8),
+                    );
+                }
+                None => bytes.extend_from_slice(&[0, 0, 0]),
+            }
+        }
+        writer.write_all(&bytes)
+    }
+
+    /// Writes a binary PGM image of the convergence speed, from white for pixels converging
+    /// immediately to black for those that did not converge.
+    pub fn write_pgm<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        write!(writer, "P5\n{} {}\n255\n", self.width, self.height)?;
+        let bytes: Vec<u8> = self
+            .pixels
+            .iter()
+            .map(|pixel| match pixel.root {
+                Some(_) => (self.speed(pixel) * 255.).round() as u8,
+                None => 0,
+            })
+            .collect();
+        writer.write_all(&bytes)
+    }
+
+    /// The convergence speed of a pixel, between 0 and 1.
+    fn speed(&self, pixel: &Pixel) -> f64 {
+        let max = self.max_iterations.max(1) as f64;
+        1. - (pixel.iterations as f64 - 1.).max(0.) / max
+    }
+}
+
+/// A saturated colour for the root of index `index`, with hues spread by the golden ratio.
+fn colour(index: usize) -> [f64; 3] {
+    let hue = (index as f64 * 0.618_033_988_75).fract() * 6.;
+    let (saturation, value) = (0.75, 1.);
+    let chroma = value * saturation;
+    let x = chroma * (1. - (hue % 2. - 1.).abs());
+    let (r, g, b) = match hue as usize {
+        0 => (chroma, x, 0.),
+        1 => (x, chroma, 0.),
+        2 => (0., chroma, x),
+        3 => (0., x, chroma),
+        4 => (x, 0., chroma),
+        _ => (chroma, 0., x),
+    };
+    let m = value - chroma;
+    [r + m, g + m, b + m]
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::NewtonFractal;
+    use crate::Complex;
+
+    fn cube_roots(width: usize, height: usize) -> super::Basins<f64> {
+        NewtonFractal::new(
+            |z: Complex<f64>| z.powi(3) - 1.,
+            |z| z * z * 3.,
+            Complex::new(-1.5, -1.),
+            Complex::new(1.5, 1.),
+            width,
+            height,
+        )
+        .with_roots(vec![Complex::new(1., 0.)])
+        .render()
+    }
+
+    #[test]
+    fn classifies_cube_roots() {
+        let basins = cube_roots(30, 20);
+
+        assert_eq!(basins.roots().len(), 3);
+        assert!((basins.roots()[0] - Complex::new(1., 0.)).norm() < 1E-12);
+        for root in basins.roots() {
+            assert!((root.powi(3) - 1.).norm() < 1E-12);
+        }
+
+        // The rightmost pixels, near the positive real axis, converge to the given root.
+        assert_eq!(basins.pixel(29, 9).root, Some(0));
+        assert_eq!(basins.pixel(29, 10).root, Some(0));
+        // The upper left and lower left corners reach the two complex roots.
+        let (upper, lower) = (basins.pixel(0, 0).root, basins.pixel(0, 19).root);
+        assert!(upper.is_some() && lower.is_some() && upper != lower);
+        assert!(upper != Some(0) && lower != Some(0));
+    }
+
+    #[test]
+    fn writes_netpbm() {
+        let basins = cube_roots(4, 3);
+
+        let mut ppm = Vec::new();
+        basins.write_ppm(&mut ppm).unwrap();
+        let header = b"P6\n4 3\n255\n";
+        assert!(ppm.starts_with(header));
+        assert_eq!(ppm.len(), header.len() + 3 * 4 * 3);
+
+        let mut pgm = Vec::new();
+        basins.write_pgm(&mut pgm).unwrap();
+        let header = b"P5\n4 3\n255\n";
+        assert!(pgm.starts_with(header));
+        assert_eq!(pgm.len(), header.len() + 4 * 3);
+        assert!(pgm[header.len()..].iter().all(|&grey| grey > 0));
+    }
+}
@@ -0,0 +1,326 @@
+use std::iter::Iterator;
+use std::ops::{Add, Div, Mul, Sub};
+
+use crate::solve::{check_derivative, solve_with};
+use crate::{Criteria, NewtonError, Real, Solution};
+
+/// An iterator that returns successive iterations of Halley's method.
+///
+/// Using the second derivative, each iteration converges cubically instead of quadratically.
+///
+/// # Example
+///
+/// ```
+/// use generic_newton::Halley;
+///
+/// let mut h = Halley::new(
+///     0.5, // Initial guess
+///     |x: f64| x.cos() - x.powi(3), // The actual function
+///     |x| -(x.sin() + 3. * x.powi(2)), // Its derivative
+///     |x| -(x.cos() + 6. * x), // Its second derivative
+/// );
+///
+/// assert!((h.nth(10).unwrap() - 0.865474033102).abs() < 1E-11)
+/// ```
+pub struct Halley<T, F, DF, DDF>
+where
+    T: Add<Output = T> + Sub<Output = T> + Mul<Output = T> + Div<Output = T> + Copy,
+    F: Fn(T) -> T,
+    DF: Fn(T) -> T,
+    DDF: Fn(T) -> T,
+{
+    current: T,
+    func: F,
+    derivative: DF,
+    second_derivative: DDF,
+}
+
+impl<T, F, DF, DDF> Halley<T, F, DF, DDF>
+where
+    T: Add<Output = T> + Sub<Output = T> + Mul<Output = T> + Div<Output = T> + Copy,
+    F: Fn(T) -> T,
+    DF: Fn(T) -> T,
+    DDF: Fn(T) -> T,
+{
+    /// Creates a new `Halley` iterator.
+    ///
+    /// - `func` is the actual function to find the root of
+    /// - `derivative` is it's derivative
+    /// - `second_derivative` is the derivative of `derivative`.
+    pub fn new(initial_guess: T, func: F, derivative: DF, second_derivative: DDF) -> Self {
+        Halley {
+            current: initial_guess,
+            func,
+            derivative,
+            second_derivative,
+        }
+    }
+
+    /// Iterates until `criteria` are met, and returns the root found.
+    ///
+    /// The denominator of the Halley step plays the role of the derivative in the returned errors.
+    pub fn solve(self, criteria: &Criteria<T>) -> Result<Solution<T>, NewtonError<T>>
+    where
+        T: Real,
+    {
+        let deriv = &self.derivative;
+        let second_deriv = &self.second_derivative;
+
+        solve_with(criteria, self.current, &self.func, |x, fx| {
+            let (numerator, denominator) = halley_ratio(fx, deriv(x), second_deriv(x));
+            Ok(x - numerator / check_derivative(x, denominator)?)
+        })
+    }
+}
+
+/// Splits the Halley step `2ff' / (2f'² - ff'')` in a numerator and a denominator.
+fn halley_ratio<T>(value: T, derivative: T, second_derivative: T) -> (T, T)
+where
+    T: Add<Output = T> + Sub<Output = T> + Mul<Output = T> + Copy,
+{
+    let numerator = value * derivative;
+    let square = derivative * derivative;
+    (
+        numerator + numerator,
+        square + square - value * second_derivative,
+    )
+}
+
+impl<T, F, DF, DDF> Iterator for Halley<T, F, DF, DDF>
+where
+    T: Add<Output = T> + Sub<Output = T> + Mul<Output = T> + Div<Output = T> + Copy,
+    F: Fn(T) -> T,
+    DF: Fn(T) -> T,
+    DDF: Fn(T) -> T,
+{
+    type Item = T;
+    fn next(&mut self) -> Option<Self::Item> {
+        let func = &self.func;
+        let deriv = &self.derivative;
+        let second_deriv = &self.second_derivative;
+
+        let (numerator, denominator) = halley_ratio(
+            func(self.current),
+            deriv(self.current),
+            second_deriv(self.current),
+        );
+        let next = self.current - numerator / denominator;
+
+        self.current = next;
+        Some(next)
+    }
+}
+
+/// An iterator that returns successive iterations of the Householder method of order `D`.
+///
+/// The derivatives up to order `D` are used to converge with order `D + 1`. `Householder` of order
+/// `1` is Newton's method, and of order `2` Halley's method.
+///
+/// # Example
+///
+/// ```
+/// use generic_newton::Householder;
+///
+/// let mut h = Householder::<_, _, _, 3>::new(
+///     0.5, // Initial guess
+///     |x: f64| x.cos() - x.powi(3), // The actual function
+///     |x| [
+///         -(x.sin() + 3. * x.powi(2)), // Its derivative
+///         -(x.cos() + 6. * x), // Its second derivative
+///         x.sin() - 6., // Its third derivative
+///     ],
+/// );
+///
+/// assert!((h.nth(10).unwrap() - 0.865474033102).abs() < 1E-11)
+/// ```
+pub struct Householder<T, F, DF, const D: usize>
+where
+    T: Add<Output = T> + Sub<Output = T> + Mul<Output = T> + Div<Output = T> + Copy,
+    F: Fn(T) -> T,
+    DF: Fn(T) -> [T; D],
+{
+    current: T,
+    func: F,
+    derivatives: DF,
+}
+
+impl<T, F, DF, const D: usize> Householder<T, F, DF, D>
+where
+    T: Add<Output = T> + Sub<Output = T> + Mul<Output = T> + Div<Output = T> + Copy,
+    F: Fn(T) -> T,
+    DF: Fn(T) -> [T; D],
+{
+    /// Creates a new `Householder` iterator.
+    ///
+    /// - `func` is the actual function to find the root of
+    /// - `derivatives` returns it's derivatives, from the first one up to order `D`.
+    ///
+    /// # Panics
+    ///
+    /// If `D` is zero.
+    pub fn new(initial_guess: T, func: F, derivatives: DF) -> Self {
+        assert!(D > 0, "Householder methods need at least one derivative");
+        Householder {
+            current: initial_guess,
+            func,
+            derivatives,
+        }
+    }
+
+    /// Iterates until `criteria` are met, and returns the root found.
+    ///
+    /// The denominator of the step plays the role of the derivative in the returned errors.
+    pub fn solve(self, criteria: &Criteria<T>) -> Result<Solution<T>, NewtonError<T>>
+    where
+        T: Real,
+    {
+        let derivs = &self.derivatives;
+
+        solve_with(criteria, self.current, &self.func, |x, fx| {
+            let (numerator, denominator) = householder_ratio(fx, &derivs(x));
+            Ok(x - numerator / check_derivative(x, denominator)?)
+        })
+    }
+}
+
+/// Adds `value` to itself until it is multiplied by `times`, which must not be zero.
+fn multiply<T: Add<Output = T> + Copy>(value: T, times: usize) -> T {
+    (1..times).fold(value, |acc, _| acc + value)
+}
+
+/// Splits the Householder step `D (1/f)^(D-1) / (1/f)^(D)` in a numerator and a denominator.
+///
+/// Writing `(1/f)^(n) = (-1)^n r_n / (f'·f^(n+1))` and differentiating `f·(1/f) = 1` with the
+/// Leibniz rule gives `r_0 = f'` and `r_n = Σ_k C(n, k) (-1)^(k+1) f^(k) f^(k-1) r_(n-k)`, so the
+/// step only needs arithmetic operations on the derivatives.
+fn householder_ratio<T, const D: usize>(value: T, derivatives: &[T; D]) -> (T, T)
+where
+    T: Add<Output = T> + Sub<Output = T> + Mul<Output = T> + Copy,
+{
+    let mut r = Vec::with_capacity(D + 1);
+    r.push(derivatives[0]);
+
+    for n in 1..=D {
+        let mut positive = None;
+        let mut negative = None;
+        let mut binomial = 1;
+        let mut power = None;
+
+        for k in 1..=n {
+            binomial = binomial * (n - k + 1) / k;
+
+            let mut term = multiply(derivatives[k - 1] * r[n - k], binomial);
+            if let Some(power) = power {
+                term = term * power;
+            }
+            power = Some(power.map_or(value, |p| p * value));
+
+            let sum = if k % 2 == 1 {
+                &mut positive
+            } else {
+                &mut negative
+            };
+            *sum = Some(sum.map_or(term, |s| s + term));
+        }
+
+        let positive = positive.expect("there is at least one term");
+        r.push(negative.map_or(positive, |negative| positive - negative));
+    }
+
+    (multiply(value * r[D - 1], D), r[D])
+}
+
+impl<T, F, DF, const D: usize> Iterator for Householder<T, F, DF, D>
+where
+    T: Add<Output = T> + Sub<Output = T> + Mul<Output = T> + Div<Output = T> + Copy,
+    F: Fn(T) -> T,
+    DF: Fn(T) -> [T; D],
+{
+    type Item = T;
+    fn next(&mut self) -> Option<Self::Item> {
+        let func = &self.func;
+        let derivs = &self.derivatives;
+
+        let (numerator, denominator) = householder_ratio(func(self.current), &derivs(self.current));
+        let next = self.current - numerator / denominator;
+
+        self.current = next;
+        Some(next)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{Halley, Householder};
+    use crate::{Criteria, Newton};
+
+    fn func(x: f64) -> f64 {
+        x.cos() - x.powi(3)
+    }
+
+    fn derivatives(x: f64) -> [f64; 3] {
+        [
+            -(x.sin() + 3. * x.powi(2)),
+            -(x.cos() + 6. * x),
+            x.sin() - 6.,
+        ]
+    }
+
+    #[test]
+    fn is_generic() {
+        let mut h = Halley::new(0, |x| x - 1, |_| 1, |_| 0);
+        assert_eq!(h.nth(5).unwrap(), 1);
+
+        let mut h = Householder::new(0., |x| x - 1., |_| [1., 0.]);
+        assert_eq!(h.nth(5).unwrap(), 1.);
+    }
+
+    #[test]
+    fn low_orders_match() {
+        let newton: Vec<_> = Newton::new(0.5, func, |x| derivatives(x)[0])
+            .take(4)
+            .collect();
+        let householder: Vec<_> = Householder::new(0.5, func, |x| [derivatives(x)[0]])
+            .take(4)
+            .collect();
+        for (n, h) in newton.iter().zip(&householder) {
+            assert!((n - h).abs() < 1E-15);
+        }
+
+        let halley: Vec<_> = Halley::new(0.5, func, |x| derivatives(x)[0], |x| derivatives(x)[1])
+            .take(4)
+            .collect();
+        let householder: Vec<_> = Householder::new(0.5, func, |x| {
+            let d = derivatives(x);
+            [d[0], d[1]]
+        })
+        .take(4)
+        .collect();
+        for (h, hh) in halley.iter().zip(&householder) {
+            assert!((h - hh).abs() < 1E-15);
+        }
+    }
+
+    #[test]
+    fn converges_faster() {
+        let criteria = Criteria::default();
+        let root = 0.865474033102;
+
+        let newton = Newton::new(0.5, func, |x| derivatives(x)[0])
+            .solve(&criteria)
+            .unwrap();
+        let halley = Halley::new(0.5, func, |x| derivatives(x)[0], |x| derivatives(x)[1])
+            .solve(&criteria)
+            .unwrap();
+        let householder = Householder::new(0.5, func, derivatives)
+            .solve(&criteria)
+            .unwrap();
+
+        for solution in &[newton, halley, householder] {
+            assert!((solution.root - root).abs() < 1E-11);
+        }
+        assert!(halley.iterations < newton.iterations);
+        assert!(householder.iterations <= halley.iterations);
+    }
+}
@@ -0,0 +1,632 @@
+use std::iter::Iterator;
+
+use crate::linalg::{dot, norm, LinearSolver, LuSolver, Matrix};
+use crate::optimize::stalled_search;
+use crate::solve::solve_system_with;
+use crate::{Criteria, NewtonError, Real, Solution};
+
+/// The result of [`InteriorPoint::solve`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct InteriorPointSolution<T> {
+    /// The minimiser found.
+    pub minimiser: Vec<T>,
+    /// The value of the function at `minimiser`.
+    pub value: T,
+    /// The Lagrange multipliers `z` of the constraints, which are non-negative.
+    pub multipliers: Vec<T>,
+    /// The slacks `s`, which are positive.
+    pub slacks: Vec<T>,
+    /// The norm of `g(x) + s`. Since the slacks are positive, it also bounds how much the
+    /// constraints are violated.
+    pub primal_infeasibility: T,
+    /// The norm of the gradient of the Lagrangian, `∇f(x) + J(x)ᵀ·z`.
+    pub dual_infeasibility: T,
+    /// The duality gap `sᵀ·z`.
+    pub duality_gap: T,
+    /// The number of iterations performed.
+    pub iterations: usize,
+}
+
+/// Splits a point of the primal–dual iteration into `x`, the slacks and the multipliers.
+fn split<T>(point: &[T], n: usize) -> (&[T], &[T], &[T]) {
+    let m = (point.len() - n) / 2;
+    let (x, duals) = point.split_at(n);
+    let (slacks, multipliers) = duals.split_at(m);
+    (x, slacks, multipliers)
+}
+
+/// The largest step `α <= 1` keeping `v + α·dv` at least a fraction `1 - τ` of `v`.
+fn max_step<T: Real>(v: &[T], dv: &[T], tau: T) -> T {
+    v.iter().zip(dv).fold(T::one(), |step, (&v, &dv)| {
+        let limit = -tau * v / dv;
+        if dv < T::zero() && limit < step {
+            limit
+        } else {
+            step
+        }
+    })
+}
+
+/// Everything about the iteration that is not the current point.
+struct State<S> {
+    solver: S,
+}
+
+impl<S> State<S> {
+    /// Computes the next point of the primal–dual iteration from `point`, where `residual` is the
+    /// residual of the KKT system at `point`, as computed by [`kkt_residual`].
+    ///
+    /// The Newton direction is computed twice: first for the KKT system itself, which predicts
+    /// how much the complementarity can be reduced, then for the KKT system perturbed by the
+    /// centering target this prediction yields, corrected to second order, as proposed by
+    /// Mehrotra. The step is then backtracked until the residual of the perturbed system
+    /// decreases. If it never does, `point` is returned when the step is below its rounding
+    /// errors, see [`stalled_search`], and the iteration fails otherwise.
+    fn advance<T, G, H, C, J>(
+        &mut self,
+        problem: &Problem<'_, G, H, C, J>,
+        point: &[T],
+        residual: &[T],
+    ) -> Result<Vec<T>, NewtonError<Vec<T>>>
+    where
+        T: Real,
+        G: Fn(&[T]) -> Vec<T>,
+        H: Fn(&[T], &[T]) -> Matrix<T>,
+        C: Fn(&[T]) -> Vec<T>,
+        J: Fn(&[T]) -> Matrix<T>,
+        S: LinearSolver<T>,
+    {
+        let n = problem.size;
+        let (x, slacks, multipliers) = split(point, n);
+        let m = slacks.len();
+        let (dual, primal) = residual[..n + m].split_at(n);
+
+        let jacobian = (problem.jacobian)(x);
+        let hessian = (problem.hessian)(x, multipliers);
+        if !jacobian.is_finite() || !hessian.is_finite() {
+            return Err(NewtonError::NonFiniteDerivative { at: x.to_vec() });
+        }
+        let transpose = jacobian.transpose();
+
+        // Eliminating the slacks and the multipliers leaves `H + Jᵀ·S⁻¹·Z·J`.
+        let reduced = Matrix::from_fn(n, n, |row, col| {
+            (0..m).fold(hessian[(row, col)], |acc, k| {
+                acc + jacobian[(k, row)] * multipliers[k] / slacks[k] * jacobian[(k, col)]
+            })
+        });
+
+        // The Newton direction for the system whose complementarity residual is `complementarity`.
+        let mut direction = |complementarity: &[T]| {
+            let weighted: Vec<T> = (0..m)
+                .map(|k| (multipliers[k] * primal[k] - complementarity[k]) / slacks[k])
+                .collect();
+            let rhs: Vec<T> = dual
+                .iter()
+                .zip(transpose.mul_vec(&weighted))
+                .map(|(&d, w)| -d - w)
+                .collect();
+            let dx = self
+                .solver
+                .solve(&reduced, &rhs)
+                .ok_or_else(|| NewtonError::ZeroDerivative { at: x.to_vec() })?;
+            let ds: Vec<T> = primal
+                .iter()
+                .zip(jacobian.mul_vec(&dx))
+                .map(|(&p, jdx)| -p - jdx)
+                .collect();
+            let dz: Vec<T> = (0..m)
+                .map(|k| (-complementarity[k] - multipliers[k] * ds[k]) / slacks[k])
+                .collect();
+            Ok((dx, ds, dz))
+        };
+
+        let complementarity: Vec<T> = slacks
+            .iter()
+            .zip(multipliers)
+            .map(|(&s, &z)| s * z)
+            .collect();
+        let mu = if m > 0 {
+            complementarity.iter().fold(T::zero(), |acc, &c| acc + c) / T::from_f64(m as f64)
+        } else {
+            T::zero()
+        };
+
+        // The predictor.
+        let (_, ds, dz) = direction(&complementarity)?;
+        let primal_step = max_step(slacks, &ds, T::one());
+        let dual_step = max_step(multipliers, &dz, T::one());
+        let sigma = if mu > T::zero() {
+            let predicted = (0..m).fold(T::zero(), |acc, k| {
+                acc + (slacks[k] + primal_step * ds[k]) * (multipliers[k] + dual_step * dz[k])
+            }) / T::from_f64(m as f64);
+            let ratio = predicted / mu;
+            if ratio < T::one() {
+                ratio * ratio * ratio
+            } else {
+                T::one()
+            }
+        } else {
+            T::zero()
+        };
+        let target = sigma * mu;
+
+        // The corrector.
+        let corrected: Vec<T> = (0..m)
+            .map(|k| complementarity[k] + ds[k] * dz[k] - target)
+            .collect();
+        let (dx, ds, dz) = direction(&corrected)?;
+        if dx.iter().chain(&ds).chain(&dz).any(|d| !d.is_finite()) {
+            return Err(NewtonError::NonFiniteDerivative { at: x.to_vec() });
+        }
+
+        let tau = T::from_f64(0.995);
+        let mut primal_step = max_step(slacks, &ds, tau);
+        let mut dual_step = max_step(multipliers, &dz, tau);
+
+        let merit = |residual: &[T]| {
+            let perturbed: Vec<T> = residual
+                .iter()
+                .enumerate()
+                .map(|(i, &r)| if i < n + m { r } else { r - target })
+                .collect();
+            norm(&perturbed)
+        };
+        let current = merit(residual);
+
+        for _ in 0..50 {
+            let next: Vec<T> = x
+                .iter()
+                .zip(&dx)
+                .map(|(&x, &d)| x + primal_step * d)
+                .chain(slacks.iter().zip(&ds).map(|(&s, &d)| s + primal_step * d))
+                .chain(
+                    multipliers
+                        .iter()
+                        .zip(&dz)
+                        .map(|(&z, &d)| z + dual_step * d),
+                )
+                .collect();
+            let smallest = if primal_step < dual_step {
+                primal_step
+            } else {
+                dual_step
+            };
+            let value = merit(&kkt_residual(problem, &next));
+            if value.is_finite() && value <= (T::one() - T::from_f64(1E-4) * smallest) * current {
+                return Ok(next);
+            }
+            let half = T::from_f64(0.5);
+            primal_step = half * primal_step;
+            dual_step = half * dual_step;
+        }
+        let step: Vec<T> = dx.into_iter().chain(ds).chain(dz).collect();
+        stalled_search(point, &step)
+    }
+}
+
+/// The functions defining the problem.
+struct Problem<'a, G, H, C, J> {
+    /// The number of variables.
+    size: usize,
+    gradient: &'a G,
+    hessian: &'a H,
+    constraints: &'a C,
+    jacobian: &'a J,
+}
+
+/// The residual of the KKT system at `point`: the gradient of the Lagrangian, `g(x) + s`, and
+/// the products `sᵢ·zᵢ`, one after the other.
+fn kkt_residual<T, G, H, C, J>(problem: &Problem<'_, G, H, C, J>, point: &[T]) -> Vec<T>
+where
+    T: Real,
+    G: Fn(&[T]) -> Vec<T>,
+    C: Fn(&[T]) -> Vec<T>,
+    J: Fn(&[T]) -> Matrix<T>,
+{
+    let (x, slacks, multipliers) = split(point, problem.size);
+    let products = (problem.jacobian)(x).transpose().mul_vec(multipliers);
+
+    (problem.gradient)(x)
+        .into_iter()
+        .zip(products)
+        .map(|(g, p)| g + p)
+        .chain(
+            (problem.constraints)(x)
+                .into_iter()
+                .zip(slacks)
+                .map(|(g, &s)| g + s),
+        )
+        .chain(slacks.iter().zip(multipliers).map(|(&s, &z)| s * z))
+        .collect()
+}
+
+/// An iterator that returns successive iterations of a primal–dual interior point method,
+/// minimising `f(x)` subject to `g(x) <= 0`.
+///
+/// Slacks `s > 0` turn the constraints into `g(x) + s = 0`, and each iteration takes a Newton
+/// step on the KKT conditions
+///
+/// ```text
+/// ∇f(x) + J(x)ᵀ·z = 0,    g(x) + s = 0,    sᵢ·zᵢ = μ,
+/// ```
+///
+/// where `J` is the Jacobian matrix of `g`, `z >= 0` are the Lagrange multipliers, and the barrier
+/// parameter `μ` is driven to zero following Mehrotra's predictor–corrector heuristic. The
+/// iterates need not be feasible, but the slacks and multipliers are kept positive. The linear
+/// systems are solved by a [`LinearSolver`], which is a dense LU decomposition by default.
+///
+/// The iterator stops when a linear system is singular, or no step decreases the residual.
+///
+/// # Example
+///
+/// ```
+/// use generic_newton::{Criteria, InteriorPoint, Matrix};
+///
+/// // The point of the unit disc closest to (2, 1).
+/// let solution = InteriorPoint::new(
+///     vec![0., 0.], // Initial guess
+///     |x: &[f64]| (x[0] - 2.).powi(2) + (x[1] - 1.).powi(2), // The function
+///     |x: &[f64]| vec![2. * (x[0] - 2.), 2. * (x[1] - 1.)], // Its gradient
+///     |_: &[f64], z: &[f64]| Matrix::from_rows(&[
+///         [2. + 2. * z[0], 0.],
+///         [0., 2. + 2. * z[0]],
+///     ]), // The Hessian of the Lagrangian
+///     |x: &[f64]| vec![x[0] * x[0] + x[1] * x[1] - 1.], // The constraints
+///     |x: &[f64]| Matrix::from_rows(&[[2. * x[0], 2. * x[1]]]), // Their Jacobian
+/// )
+/// .solve(&Criteria::default())
+/// .unwrap();
+///
+/// let minimiser = solution.minimiser;
+/// let expected = 5f64.sqrt();
+/// assert!((minimiser[0] - 2. / expected).abs() < 1E-12);
+/// assert!((minimiser[1] - 1. / expected).abs() < 1E-12);
+/// ```
+pub struct InteriorPoint<T, F, G, H, C, J, S = LuSolver>
+where
+    T: Real,
+    F: Fn(&[T]) -> T,
+    G: Fn(&[T]) -> Vec<T>,
+    H: Fn(&[T], &[T]) -> Matrix<T>,
+    C: Fn(&[T]) -> Vec<T>,
+    J: Fn(&[T]) -> Matrix<T>,
+    S: LinearSolver<T>,
+{
+    current: Vec<T>,
+    /// The slacks and multipliers, once initialised.
+    duals: Option<Vec<T>>,
+    func: F,
+    gradient: G,
+    hessian: H,
+    constraints: C,
+    jacobian: J,
+    state: State<S>,
+}
+
+impl<T, F, G, H, C, J> InteriorPoint<T, F, G, H, C, J>
+where
+    T: Real,
+    F: Fn(&[T]) -> T,
+    G: Fn(&[T]) -> Vec<T>,
+    H: Fn(&[T], &[T]) -> Matrix<T>,
+    C: Fn(&[T]) -> Vec<T>,
+    J: Fn(&[T]) -> Matrix<T>,
+{
+    /// Creates a new `InteriorPoint` iterator.
+    ///
+    /// - `func` is the actual function to minimise
+    /// - `gradient` is it's gradient
+    /// - `hessian(x, z)` is the Hessian matrix of the Lagrangian `f(x) + zᵀ·g(x)`
+    /// - `constraints` is `g`, whose components must all be non-positive
+    /// - `jacobian` is the Jacobian matrix of `g`.
+    ///
+    /// The slacks start at `max(-g(x), 1)` and the multipliers at one.
+    pub fn new(
+        initial_guess: Vec<T>,
+        func: F,
+        gradient: G,
+        hessian: H,
+        constraints: C,
+        jacobian: J,
+    ) -> Self {
+        InteriorPoint {
+            current: initial_guess,
+            duals: None,
+            func,
+            gradient,
+            hessian,
+            constraints,
+            jacobian,
+            state: State { solver: LuSolver },
+        }
+    }
+}
+
+impl<T, F, G, H, C, J, S> InteriorPoint<T, F, G, H, C, J, S>
+where
+    T: Real,
+    F: Fn(&[T]) -> T,
+    G: Fn(&[T]) -> Vec<T>,
+    H: Fn(&[T], &[T]) -> Matrix<T>,
+    C: Fn(&[T]) -> Vec<T>,
+    J: Fn(&[T]) -> Matrix<T>,
+    S: LinearSolver<T>,
+{
+    /// Replaces the solver used for the linear systems.
+    pub fn with_solver<S2: LinearSolver<T>>(
+        self,
+        solver: S2,
+    ) -> InteriorPoint<T, F, G, H, C, J, S2> {
+        InteriorPoint {
+            current: self.current,
+            duals: self.duals,
+            func: self.func,
+            gradient: self.gradient,
+            hessian: self.hessian,
+            constraints: self.constraints,
+            jacobian: self.jacobian,
+            state: State { solver },
+        }
+    }
+
+    /// The current point of the primal–dual iteration, initialising the slacks and multipliers if
+    /// needed.
+    fn point(&self) -> Vec<T> {
+        let duals = self.duals.clone().unwrap_or_else(|| {
+            let constraints = (self.constraints)(&self.current);
+            let slacks = constraints
+                .iter()
+                .map(|&g| if -g > T::one() { -g } else { T::one() });
+            slacks.chain(constraints.iter().map(|_| T::one())).collect()
+        });
+        self.current.iter().copied().chain(duals).collect()
+    }
+
+    /// Iterates until `criteria` are met, and returns the minimiser found.
+    ///
+    /// The residual tolerance of `criteria` applies to the norm of the residual of the KKT system,
+    /// and the step tolerances to the primal–dual iterates. A singular linear system is reported
+    /// as [`NewtonError::ZeroDerivative`]. The iteration also stops when the steps fall below the
+    /// rounding errors of the iterates. Otherwise, when no step decreases the residual, it fails
+    /// with [`NewtonError::LineSearchFailed`].
+    pub fn solve(
+        mut self,
+        criteria: &Criteria<T>,
+    ) -> Result<InteriorPointSolution<T>, NewtonError<Vec<T>>> {
+        let point = self.point();
+        let problem = Problem {
+            size: self.current.len(),
+            gradient: &self.gradient,
+            hessian: &self.hessian,
+            constraints: &self.constraints,
+            jacobian: &self.jacobian,
+        };
+        let state = &mut self.state;
+
+        let solution = solve_system_with(
+            criteria,
+            point,
+            |point| kkt_residual(&problem, point),
+            |point, residual| state.advance(&problem, point, residual),
+        )?;
+
+        let Solution {
+            root,
+            residual,
+            iterations,
+        } = solution;
+        let n = problem.size;
+        let (x, slacks, multipliers) = split(&root, n);
+        let m = slacks.len();
+        Ok(InteriorPointSolution {
+            value: (self.func)(x),
+            minimiser: x.to_vec(),
+            multipliers: multipliers.to_vec(),
+            slacks: slacks.to_vec(),
+            dual_infeasibility: norm(&residual[..n]),
+            primal_infeasibility: norm(&residual[n..n + m]),
+            duality_gap: dot(slacks, multipliers),
+            iterations,
+        })
+    }
+}
+
+impl<T, F, G, H, C, J, S> Iterator for InteriorPoint<T, F, G, H, C, J, S>
+where
+    T: Real,
+    F: Fn(&[T]) -> T,
+    G: Fn(&[T]) -> Vec<T>,
+    H: Fn(&[T], &[T]) -> Matrix<T>,
+    C: Fn(&[T]) -> Vec<T>,
+    J: Fn(&[T]) -> Matrix<T>,
+    S: LinearSolver<T>,
+{
+    type Item = Vec<T>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let point = self.point();
+        let problem = Problem {
+            size: self.current.len(),
+            gradient: &self.gradient,
+            hessian: &self.hessian,
+            constraints: &self.constraints,
+            jacobian: &self.jacobian,
+        };
+        let residual = kkt_residual(&problem, &point);
+        let mut next = self.state.advance(&problem, &point, &residual).ok()?;
+
+        let duals = next.split_off(self.current.len());
+        self.duals = Some(duals);
+        self.current = next;
+        Some(self.current.clone())
+    }
+}
+
+/// Creates an [`InteriorPoint`] iterator minimising the quadratic `½·xᵀ·Q·x + cᵀ·x` subject to
+/// the linear constraints `A·x <= b`, starting from the origin.
+///
+/// `Q` must be symmetric positive semi-definite for the problem to be convex.
+///
+/// # Panics
+///
+/// If the dimensions of `q`, `c`, `a` and `b` do not match.
+///
+/// # Example
+///
+/// ```
+/// use generic_newton::{quadratic_program, Criteria, Matrix};
+///
+/// // The point of the triangle x >= 0, y >= 0, x + y <= 1 closest to (1, 1).
+/// let solution = quadratic_program(
+///     Matrix::identity(2),
+///     vec![-1., -1.],
+///     Matrix::from_rows(&[[-1., 0.], [0., -1.], [1., 1.]]),
+///     vec![0., 0., 1.],
+/// )
+/// .solve(&Criteria::default())
+/// .unwrap();
+///
+/// assert!((solution.minimiser[0] - 0.5f64).abs() < 1E-12);
+/// assert!((solution.minimiser[1] - 0.5f64).abs() < 1E-12);
+/// assert!((solution.multipliers[2] - 0.5f64).abs() < 1E-12);
+/// ```
+#[allow(clippy::type_complexity)]
+pub fn quadratic_program<T: Real>(
+    q: Matrix<T>,
+    c: Vec<T>,
+    a: Matrix<T>,
+    b: Vec<T>,
+) -> InteriorPoint<
+    T,
+    impl Fn(&[T]) -> T,
+    impl Fn(&[T]) -> Vec<T>,
+    impl Fn(&[T], &[T]) -> Matrix<T>,
+    impl Fn(&[T]) -> Vec<T>,
+    impl Fn(&[T]) -> Matrix<T>,
+> {
+    let n = c.len();
+    assert!(
+        q.rows() == n && q.cols() == n && a.cols() == n && a.rows() == b.len(),
+        "dimension mismatch"
+    );
+
+    let (q1, q2, c1, c2) = (q.clone(), q.clone(), c.clone(), c);
+    InteriorPoint::new(
+        vec![T::zero(); n],
+        move |x: &[T]| T::from_f64(0.5) * dot(x, &q1.mul_vec(x)) + dot(&c1, x),
+        move |x: &[T]| {
+            q2.mul_vec(x)
+                .into_iter()
+                .zip(&c2)
+                .map(|(qx, &c)| qx + c)
+                .collect()
+        },
+        move |_: &[T], _: &[T]| q.clone(),
+        {
+            let a = a.clone();
+            move |x: &[T]| {
+                a.mul_vec(x)
+                    .into_iter()
+                    .zip(&b)
+                    .map(|(ax, &b)| ax - b)
+                    .collect()
+            }
+        },
+        move |_: &[T]| a.clone(),
+    )
+}
+
+/// Creates an [`InteriorPoint`] iterator minimising `cᵀ·x` subject to the linear constraints
+/// `A·x <= b`, starting from the origin.
+///
+/// The problem must be bounded, which is easiest to ensure by bounding each variable.
+///
+/// # Panics
+///
+/// If the dimensions of `c`, `a` and `b` do not match.
+#[allow(clippy::type_complexity)]
+pub fn linear_program<T: Real>(
+    c: Vec<T>,
+    a: Matrix<T>,
+    b: Vec<T>,
+) -> InteriorPoint<
+    T,
+    impl Fn(&[T]) -> T,
+    impl Fn(&[T]) -> Vec<T>,
+    impl Fn(&[T], &[T]) -> Matrix<T>,
+    impl Fn(&[T]) -> Vec<T>,
+    impl Fn(&[T]) -> Matrix<T>,
+> {
+    let n = c.len();
+    quadratic_program(Matrix::zeros(n, n), c, a, b)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{linear_program, InteriorPoint};
+    use crate::{Criteria, Matrix, NewtonError};
+
+    #[test]
+    fn linear_program_vertex() {
+        // Maximises 3x + 2y subject to x + y <= 4, x + 3y <= 6, x <= 3.5, x >= 0 and y >= 0. The
+        // optimum is at the vertex (3.5, 0.5).
+        let a = Matrix::from_rows(&[[1f64, 1.], [1., 3.], [1., 0.], [-1., 0.], [0., -1.]]);
+        let solution = linear_program(vec![-3., -2.], a, vec![4., 6., 3.5, 0., 0.])
+            .solve(&Criteria::default())
+            .unwrap();
+
+        assert!((solution.minimiser[0] - 3.5).abs() < 1E-10);
+        assert!((solution.minimiser[1] - 0.5).abs() < 1E-10);
+        assert!((solution.value + 11.5).abs() < 1E-10);
+        assert!(solution.primal_infeasibility < 1E-10);
+        assert!(solution.dual_infeasibility < 1E-10);
+        assert!(solution.duality_gap < 1E-10);
+        // Only x + y <= 4 and x <= 3.5 are active, with multipliers 2 and 1.
+        let expected = [2., 0., 1., 0., 0.];
+        for (z, expected) in solution.multipliers.iter().zip(&expected) {
+            assert!((z - expected).abs() < 1E-8);
+        }
+    }
+
+    #[test]
+    fn infeasible_start_nonlinear() {
+        // Minimises x + y inside the disc of radius 2 centred at (3, 0), starting outside of it.
+        let criteria = Criteria {
+            residual_tolerance: 1E-12,
+            ..Criteria::default()
+        };
+        let solution = InteriorPoint::new(
+            vec![0f64, 0.],
+            |x: &[f64]| x[0] + x[1],
+            |_: &[f64]| vec![1., 1.],
+            |_: &[f64], z: &[f64]| Matrix::from_rows(&[[2. * z[0], 0.], [0., 2. * z[0]]]),
+            |x: &[f64]| vec![(x[0] - 3.).powi(2) + x[1] * x[1] - 4.],
+            |x: &[f64]| Matrix::from_rows(&[[2. * (x[0] - 3.), 2. * x[1]]]),
+        )
+        .solve(&criteria)
+        .unwrap();
+
+        let offset = 2f64.sqrt();
+        assert!((solution.minimiser[0] - (3. - offset)).abs() < 1E-10);
+        assert!((solution.minimiser[1] + offset).abs() < 1E-10);
+        assert!((solution.multipliers[0] - 1. / (2. * offset)).abs() < 1E-10);
+        assert!(solution.slacks[0] > 0.);
+    }
+
+    #[test]
+    fn reports_infeasible_program() {
+        // x <= -1 and x >= 1: no step reduces the residual of the KKT system.
+        let result = linear_program(
+            vec![1f64],
+            Matrix::from_rows(&[[1.], [-1.]]),
+            vec![-1., -1.],
+        )
+        .solve(&Criteria::default());
+        assert_eq!(
+            result,
+            Err(NewtonError::LineSearchFailed {
+                at: vec![0., 1., 1., 1., 1.]
+            })
+        );
+    }
+}
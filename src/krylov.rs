@@ -0,0 +1,313 @@
+use std::iter::Iterator;
+
+use crate::linalg::{norm, Gmres, Preconditioner};
+use crate::solve::solve_system_with;
+use crate::{Criteria, NewtonError, Real, Solution};
+
+/// How precisely each linear system is solved by [`NewtonKrylov`].
+///
+/// The linear solve stops once `‖F(x) - J·d‖ <= η·‖F(x)‖`, where `η` is the forcing term.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Forcing<T> {
+    /// Uses the same forcing term at every iteration.
+    Constant(T),
+    /// Uses the second choice of Eisenstat and Walker, `η = γ·(‖F(xₖ)‖ / ‖F(xₖ₋₁)‖)^α`, capped
+    /// by `max`, which solves loosely far from the root and precisely close to it.
+    EisenstatWalker {
+        /// The factor `γ`, between zero and one.
+        gamma: T,
+        /// The exponent `α`, between one and two.
+        alpha: T,
+        /// The largest forcing term allowed.
+        max: T,
+    },
+}
+
+/// Everything about the iteration that is not the current point.
+struct State<T> {
+    forcing: Forcing<T>,
+    gmres: Gmres<T>,
+    /// The norm of the last residual, and the forcing term used for it.
+    previous: Option<(T, T)>,
+}
+
+impl<T: Real> State<T> {
+    /// The forcing term to use for a residual of norm `residual_norm`.
+    fn forcing_term(&self, residual_norm: T) -> T {
+        match self.forcing {
+            Forcing::Constant(eta) => eta,
+            Forcing::EisenstatWalker { gamma, alpha, max } => match self.previous {
+                None => max,
+                Some((previous_norm, previous_eta)) => {
+                    let mut eta = gamma * (residual_norm / previous_norm).powf(alpha);
+                    // Avoids oversolving when the forcing terms drop too quickly.
+                    let safeguard = gamma * previous_eta.powf(alpha);
+                    if safeguard > T::from_f64(0.1) && safeguard > eta {
+                        eta = safeguard;
+                    }
+                    if eta > max {
+                        max
+                    } else {
+                        eta
+                    }
+                }
+            },
+        }
+    }
+
+    /// Computes the inexact Newton step from `x`, where `fx` is `func(x)`.
+    fn advance<F: Fn(&[T]) -> Vec<T>>(
+        &mut self,
+        func: &F,
+        preconditioner: Option<&Preconditioner<'_, T>>,
+        x: &[T],
+        fx: &[T],
+    ) -> Result<Vec<T>, NewtonError<Vec<T>>> {
+        let residual_norm = norm(fx);
+        let eta = self.forcing_term(residual_norm);
+        self.previous = Some((residual_norm, eta));
+
+        // J·v ≈ (F(x + h·v) - F(x)) / h, with h scaled to the size of x and v.
+        let scale = T::epsilon().sqrt() * (T::one() + norm(x));
+        let product = |v: &[T]| {
+            let v_norm = norm(v);
+            if v_norm == T::zero() {
+                return vec![T::zero(); v.len()];
+            }
+            let h = scale / v_norm;
+            let shifted: Vec<T> = x.iter().zip(v).map(|(&x, &v)| x + h * v).collect();
+            func(&shifted)
+                .iter()
+                .zip(fx)
+                .map(|(&s, &f)| (s - f) / h)
+                .collect()
+        };
+
+        let gmres = Gmres {
+            tolerance: eta,
+            ..self.gmres
+        };
+        let result = gmres.solve(product, preconditioner, fx);
+
+        if result.solution.iter().any(|d| !d.is_finite()) {
+            return Err(NewtonError::NonFiniteDerivative { at: x.to_vec() });
+        }
+        if result.residual_norm >= residual_norm {
+            return Err(NewtonError::ZeroDerivative { at: x.to_vec() });
+        }
+        Ok(x.iter().zip(result.solution).map(|(&x, d)| x - d).collect())
+    }
+}
+
+/// An iterator that returns successive iterations of the Jacobian-free Newton–Krylov method.
+///
+/// The Jacobian is never formed: each Newton step is computed by [`Gmres`], using directional
+/// finite differences of the function to compute products of the Jacobian with vectors. The
+/// linear systems are only solved up to a [`Forcing`] term, which is chosen by the Eisenstat–Walker
+/// rule by default.
+///
+/// The iterator stops when no step reducing the linear residual can be found.
+///
+/// # Example
+///
+/// ```
+/// use generic_newton::NewtonKrylov;
+///
+/// // The discretised Bratu problem -u'' = eᵘ on [0, 1], with u = 0 on the boundary.
+/// let n = 50;
+/// let h = 1. / (n + 1) as f64;
+/// let bratu = move |u: &[f64]| {
+///     (0..n)
+///         .map(|i| {
+///             let left = if i > 0 { u[i - 1] } else { 0. };
+///             let right = if i + 1 < n { u[i + 1] } else { 0. };
+///             (2. * u[i] - left - right) / (h * h) - u[i].exp()
+///         })
+///         .collect()
+/// };
+///
+/// let mut nk = NewtonKrylov::new(vec![0.; n], bratu);
+///
+/// let u = nk.nth(10).unwrap();
+/// assert!((u[n / 2] - 0.14).abs() < 1E-2);
+/// ```
+pub struct NewtonKrylov<T, F, P = fn(&[T]) -> Vec<T>>
+where
+    T: Real,
+    F: Fn(&[T]) -> Vec<T>,
+    P: Fn(&[T]) -> Vec<T>,
+{
+    current: Vec<T>,
+    func: F,
+    preconditioner: Option<P>,
+    state: State<T>,
+}
+
+impl<T, F> NewtonKrylov<T, F>
+where
+    T: Real,
+    F: Fn(&[T]) -> Vec<T>,
+{
+    /// Creates a new `NewtonKrylov` iterator.
+    ///
+    /// - `func` is the actual function to find the root of
+    pub fn new(initial_guess: Vec<T>, func: F) -> Self {
+        NewtonKrylov {
+            current: initial_guess,
+            func,
+            preconditioner: None,
+            state: State {
+                forcing: Forcing::EisenstatWalker {
+                    gamma: T::from_f64(0.9),
+                    alpha: T::from_f64(2.),
+                    max: T::from_f64(0.9),
+                },
+                gmres: Gmres::default(),
+                previous: None,
+            },
+        }
+    }
+}
+
+impl<T, F, P> NewtonKrylov<T, F, P>
+where
+    T: Real,
+    F: Fn(&[T]) -> Vec<T>,
+    P: Fn(&[T]) -> Vec<T>,
+{
+    /// Sets how precisely the linear systems are solved.
+    pub fn with_forcing(mut self, forcing: Forcing<T>) -> Self {
+        self.state.forcing = forcing;
+        self
+    }
+
+    /// Sets the parameters of the linear solver, whose tolerance is overridden by the forcing
+    /// term.
+    pub fn with_gmres(mut self, gmres: Gmres<T>) -> Self {
+        self.state.gmres = gmres;
+        self
+    }
+
+    /// Sets a right preconditioner, which should approximate the product of the inverse of the
+    /// Jacobian with its argument. It may borrow local data, such as a factorised matrix.
+    pub fn with_preconditioner<P2>(self, preconditioner: P2) -> NewtonKrylov<T, F, P2>
+    where
+        P2: Fn(&[T]) -> Vec<T>,
+    {
+        NewtonKrylov {
+            current: self.current,
+            func: self.func,
+            preconditioner: Some(preconditioner),
+            state: self.state,
+        }
+    }
+
+    /// Iterates until `criteria` are met, and returns the root found.
+    ///
+    /// A step that does not reduce the linear residual is reported as
+    /// [`NewtonError::ZeroDerivative`].
+    pub fn solve(self, criteria: &Criteria<T>) -> Result<Solution<Vec<T>>, NewtonError<Vec<T>>> {
+        let func = &self.func;
+        let preconditioner = self
+            .preconditioner
+            .as_ref()
+            .map(|p| p as &Preconditioner<'_, T>);
+        let mut state = self.state;
+
+        solve_system_with(criteria, self.current, func, |x, fx| {
+            state.advance(func, preconditioner, x, fx)
+        })
+    }
+}
+
+impl<T, F, P> Iterator for NewtonKrylov<T, F, P>
+where
+    T: Real,
+    F: Fn(&[T]) -> Vec<T>,
+    P: Fn(&[T]) -> Vec<T>,
+{
+    type Item = Vec<T>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let func = &self.func;
+        let preconditioner = self
+            .preconditioner
+            .as_ref()
+            .map(|p| p as &Preconditioner<'_, T>);
+
+        let value = func(&self.current);
+        self.current = self
+            .state
+            .advance(func, preconditioner, &self.current, &value)
+            .ok()?;
+        Some(self.current.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{Forcing, NewtonKrylov};
+    use crate::{Criteria, Matrix, NewtonSystem};
+
+    /// A discretised nonlinear diffusion problem.
+    fn func(u: &[f64]) -> Vec<f64> {
+        let n = u.len();
+        (0..n)
+            .map(|i| {
+                let left = if i > 0 { u[i - 1] } else { 0. };
+                let right = if i + 1 < n { u[i + 1] } else { 0. };
+                3. * u[i] - left - right + u[i].powi(3) - 1.
+            })
+            .collect()
+    }
+
+    fn jacobian(u: &[f64]) -> Matrix<f64> {
+        Matrix::from_fn(u.len(), u.len(), |row, col| {
+            if row == col {
+                3. + 3. * u[row].powi(2)
+            } else if row == col + 1 || col == row + 1 {
+                -1.
+            } else {
+                0.
+            }
+        })
+    }
+
+    #[test]
+    fn matches_newton() {
+        let n = 40;
+        let criteria = Criteria {
+            residual_tolerance: 1E-10,
+            ..Criteria::default()
+        };
+
+        let exact = NewtonSystem::new(vec![0.; n], func, jacobian)
+            .solve(&criteria)
+            .unwrap();
+
+        for forcing in &[
+            Forcing::Constant(1E-6),
+            Forcing::EisenstatWalker {
+                gamma: 0.9,
+                alpha: 2.,
+                max: 0.9,
+            },
+        ] {
+            let inexact = NewtonKrylov::new(vec![0.; n], func)
+                .with_forcing(*forcing)
+                .solve(&criteria)
+                .unwrap();
+            for (a, b) in exact.root.iter().zip(&inexact.root) {
+                assert!((a - b).abs() < 1E-9);
+            }
+        }
+
+        // The preconditioner borrows the inverse of the diagonal of the Jacobian at the start.
+        let diagonal = vec![1. / 3.; n];
+        let preconditioned = NewtonKrylov::new(vec![0.; n], func)
+            .with_preconditioner(|v: &[f64]| v.iter().zip(&diagonal).map(|(x, d)| x * d).collect())
+            .solve(&criteria)
+            .unwrap();
+        assert!(preconditioned.residual.iter().all(|r| r.abs() < 1E-10));
+    }
+}
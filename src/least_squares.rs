@@ -0,0 +1,439 @@
+use std::cell::Cell;
+use std::iter::Iterator;
+
+use crate::linalg::{dot, Matrix, Qr};
+use crate::solve::solve_system_with;
+use crate::system::check_shape;
+use crate::{Criteria, NewtonError, Real};
+
+/// How the steps of [`LeastSquares`] are computed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LeastSquaresMethod<T> {
+    /// Takes full Gauss–Newton steps, which converge quickly on problems with small residuals but
+    /// may diverge far from the solution.
+    GaussNewton,
+    /// Damps the Gauss–Newton steps with the Levenberg–Marquardt method, starting from the given
+    /// damping, which is relative to the norms of the columns of the Jacobian.
+    LevenbergMarquardt(T),
+}
+
+/// The result of [`LeastSquares::solve`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct LeastSquaresSolution<T> {
+    /// The fitted parameters.
+    pub parameters: Vec<T>,
+    /// The residuals at the fitted parameters.
+    pub residuals: Vec<T>,
+    /// The sum of the squares of the residuals.
+    pub sum_of_squares: T,
+    /// The number of iterations performed.
+    pub iterations: usize,
+}
+
+/// The next parameters, along with the residuals there if they were computed.
+type Advance<T> = Result<(Vec<T>, Option<Vec<T>>), NewtonError<Vec<T>>>;
+
+/// Everything about the iteration that is not the current point.
+struct State<T> {
+    method: LeastSquaresMethod<T>,
+    /// The current Levenberg–Marquardt damping.
+    damping: T,
+    /// The factor the damping is multiplied by when a step is rejected.
+    growth: T,
+    /// The largest norm seen of each column of the Jacobian, which scales the damping.
+    scaling: Vec<T>,
+}
+
+impl<T: Real> State<T> {
+    /// Computes the next parameters from `x`, where `fx` is `func(x)`.
+    fn advance<F, J>(&mut self, func: &F, jacobian: &J, x: &[T], fx: &[T]) -> Advance<T>
+    where
+        F: Fn(&[T]) -> Vec<T>,
+        J: Fn(&[T]) -> Matrix<T>,
+    {
+        // The QR decompositions need at least as many residuals as parameters.
+        if fx.len() < x.len() {
+            return Err(NewtonError::DimensionMismatch {
+                expected: x.len(),
+                found: fx.len(),
+            });
+        }
+        let jac = jacobian(x);
+        check_shape(&jac, fx.len(), x.len())?;
+        if !jac.is_finite() {
+            return Err(NewtonError::NonFiniteDerivative { at: x.to_vec() });
+        }
+        let negative: Vec<T> = fx.iter().map(|&r| -r).collect();
+
+        match self.method {
+            LeastSquaresMethod::GaussNewton => {
+                let step = Qr::new(&jac)
+                    .solve_least_squares(&negative)
+                    .ok_or_else(|| NewtonError::ZeroDerivative { at: x.to_vec() })?;
+                Ok((add(x, &step), None))
+            }
+            LeastSquaresMethod::LevenbergMarquardt(_) => self.damped(func, &jac, x, fx, negative),
+        }
+    }
+
+    /// Tries Levenberg–Marquardt steps of increasing damping until one reduces the sum of
+    /// squares.
+    ///
+    /// When the damping grows so large that the steps vanish without any of them reducing the sum
+    /// of squares, `x` is returned unchanged if it is stationary to working precision, and the
+    /// iteration fails with [`NewtonError::LineSearchFailed`] otherwise.
+    fn damped<F>(
+        &mut self,
+        func: &F,
+        jac: &Matrix<T>,
+        x: &[T],
+        fx: &[T],
+        negative: Vec<T>,
+    ) -> Advance<T>
+    where
+        F: Fn(&[T]) -> Vec<T>,
+    {
+        let (m, n) = (jac.rows(), jac.cols());
+        self.scaling.resize(n, T::zero());
+        for (col, scale) in self.scaling.iter_mut().enumerate() {
+            let column_norm = (0..m)
+                .fold(T::zero(), |acc, row| {
+                    acc + jac[(row, col)] * jac[(row, col)]
+                })
+                .sqrt();
+            if column_norm > *scale {
+                *scale = column_norm;
+            }
+        }
+
+        // The damped step minimises ‖F + J·p‖² + λ‖D·p‖², which is the linear least squares
+        // problem of the Jacobian augmented with √λ·D.
+        let mut rhs = negative;
+        rhs.resize(m + n, T::zero());
+        let current = dot(fx, fx);
+        let initial_damping = self.damping;
+
+        loop {
+            let root = self.damping.sqrt();
+            let scaling = &self.scaling;
+            let augmented = Matrix::from_fn(m + n, n, |row, col| {
+                if row < m {
+                    jac[(row, col)]
+                } else if row - m == col && scaling[col] > T::zero() {
+                    root * scaling[col]
+                } else if row - m == col {
+                    root
+                } else {
+                    T::zero()
+                }
+            });
+            let step = Qr::new(&augmented)
+                .solve_least_squares(&rhs)
+                .ok_or_else(|| NewtonError::ZeroDerivative { at: x.to_vec() })?;
+
+            let next = add(x, &step);
+            let next_value = func(&next);
+
+            let model = add(fx, &jac.mul_vec(&step));
+            let predicted = current - dot(&model, &model);
+            let actual = current - dot(&next_value, &next_value);
+            let ratio = if predicted > T::zero() && actual.is_finite() {
+                actual / predicted
+            } else {
+                T::zero()
+            };
+
+            if ratio > T::from_f64(1E-4) {
+                // Nielsen's update, which decreases the damping smoothly after good steps.
+                let t = ratio + ratio - T::one();
+                let factor = T::one() - t * t * t;
+                let third = T::one() / T::from_f64(3.);
+                self.damping = self.damping * if factor > third { factor } else { third };
+                self.growth = T::from_f64(2.);
+                return Ok((next, Some(next_value)));
+            }
+
+            self.damping = if self.damping > T::zero() {
+                self.damping * self.growth
+            } else {
+                T::epsilon()
+            };
+            self.growth = self.growth + self.growth;
+            if self.damping * T::epsilon() >= T::one() {
+                self.damping = initial_damping;
+                self.growth = T::from_f64(2.);
+
+                // Near a minimum with nonzero residuals, even the undamped step predicts a decrease
+                // below the rounding errors of the sum of squares: `x` is then stationary to
+                // working precision, and returned unchanged as converged.
+                let stationary = if let Some(step) = Qr::new(jac).solve_least_squares(&rhs[..m]) {
+                    let model = add(fx, &jac.mul_vec(&step));
+                    current - dot(&model, &model) <= T::from_f64(16.) * T::epsilon() * current
+                } else {
+                    false
+                };
+                return if stationary {
+                    Ok((x.to_vec(), Some(fx.to_vec())))
+                } else {
+                    Err(NewtonError::LineSearchFailed { at: x.to_vec() })
+                };
+            }
+        }
+    }
+}
+
+/// Adds two vectors.
+fn add<T: Real>(a: &[T], b: &[T]) -> Vec<T> {
+    a.iter().zip(b).map(|(&a, &b)| a + b).collect()
+}
+
+/// An iterator that returns successive iterations of a nonlinear least squares method.
+///
+/// The parameters `p` are chosen to minimise `‖r(p)‖²`, where the residuals `r` may outnumber the
+/// parameters. Each step solves a linearised problem with a [`Qr`] decomposition of the Jacobian,
+/// avoiding the normal equations.
+///
+/// By default, the Levenberg–Marquardt method is used: steps that do not reduce the sum of squares
+/// are rejected and retried with more damping, and the damping is reduced after successful steps.
+///
+/// The iterator stops when the Jacobian is rank deficient.
+///
+/// # Example
+///
+/// ```
+/// use generic_newton::{LeastSquares, Matrix};
+///
+/// // Fits y = a·exp(b·t) to points lying on y = 2·exp(-t / 2).
+/// let ts = [0f64, 1., 2., 3., 4., 5.];
+/// let ys: Vec<f64> = ts.iter().map(|t| 2. * (-t / 2.).exp()).collect();
+///
+/// let mut ls = LeastSquares::new(
+///     vec![1., 0.], // Initial guess
+///     |p: &[f64]| ts.iter().zip(&ys).map(|(t, y)| p[0] * (p[1] * t).exp() - y).collect(),
+///     |p: &[f64]| Matrix::from_fn(6, 2, |i, j| {
+///         let e = (p[1] * ts[i]).exp();
+///         if j == 0 { e } else { p[0] * ts[i] * e }
+///     }),
+/// );
+///
+/// let p = ls.nth(50).unwrap();
+/// assert!((p[0] - 2.).abs() < 1E-12 && (p[1] + 0.5).abs() < 1E-12);
+/// ```
+pub struct LeastSquares<T, F, J>
+where
+    T: Real,
+    F: Fn(&[T]) -> Vec<T>,
+    J: Fn(&[T]) -> Matrix<T>,
+{
+    current: Vec<T>,
+    current_value: Option<Vec<T>>,
+    func: F,
+    jacobian: J,
+    state: State<T>,
+}
+
+impl<T, F, J> LeastSquares<T, F, J>
+where
+    T: Real,
+    F: Fn(&[T]) -> Vec<T>,
+    J: Fn(&[T]) -> Matrix<T>,
+{
+    /// Creates a new `LeastSquares` iterator, using the Levenberg–Marquardt method with an initial
+    /// damping of `1E-3`.
+    ///
+    /// - `func` computes the residuals for some parameters
+    /// - `jacobian` is it's Jacobian matrix, with one row per residual.
+    pub fn new(initial_guess: Vec<T>, func: F, jacobian: J) -> Self {
+        let damping = T::from_f64(1E-3);
+        LeastSquares {
+            current: initial_guess,
+            current_value: None,
+            func,
+            jacobian,
+            state: State {
+                method: LeastSquaresMethod::LevenbergMarquardt(damping),
+                damping,
+                growth: T::from_f64(2.),
+                scaling: Vec::new(),
+            },
+        }
+    }
+
+    /// Sets the method used to compute the steps.
+    pub fn with_method(mut self, method: LeastSquaresMethod<T>) -> Self {
+        if let LeastSquaresMethod::LevenbergMarquardt(damping) = method {
+            self.state.damping = damping;
+        }
+        self.state.method = method;
+        self
+    }
+
+    /// Iterates until `criteria` are met, and returns the fitted parameters.
+    ///
+    /// The residual tolerance of `criteria` applies to the norm of the residuals, which usually
+    /// does not vanish: the iteration rather stops once the steps are small enough. A
+    /// rank-deficient Jacobian is reported as [`NewtonError::ZeroDerivative`], and fewer residuals
+    /// than parameters, or a Jacobian not of size residuals × parameters, as
+    /// [`NewtonError::DimensionMismatch`].
+    pub fn solve(
+        self,
+        criteria: &Criteria<T>,
+    ) -> Result<LeastSquaresSolution<T>, NewtonError<Vec<T>>> {
+        let func = &self.func;
+        let jacobian = &self.jacobian;
+        let mut state = self.state;
+        let cached = Cell::new(self.current_value);
+
+        let solution = solve_system_with(
+            criteria,
+            self.current,
+            |x| cached.take().unwrap_or_else(|| func(x)),
+            |x, fx| {
+                let (next, next_value) = state.advance(func, jacobian, x, fx)?;
+                cached.set(next_value);
+                Ok(next)
+            },
+        )?;
+
+        Ok(LeastSquaresSolution {
+            sum_of_squares: dot(&solution.residual, &solution.residual),
+            parameters: solution.root,
+            residuals: solution.residual,
+            iterations: solution.iterations,
+        })
+    }
+}
+
+impl<T, F, J> Iterator for LeastSquares<T, F, J>
+where
+    T: Real,
+    F: Fn(&[T]) -> Vec<T>,
+    J: Fn(&[T]) -> Matrix<T>,
+{
+    type Item = Vec<T>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let func = &self.func;
+
+        let value = match self.current_value.take() {
+            Some(value) => value,
+            None => func(&self.current),
+        };
+        let (next, next_value) = self
+            .state
+            .advance(func, &self.jacobian, &self.current, &value)
+            .ok()?;
+
+        self.current = next;
+        self.current_value = next_value;
+        Some(self.current.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{LeastSquares, LeastSquaresMethod};
+    use crate::{Criteria, Matrix, NewtonError};
+
+    const TS: [f64; 8] = [0., 0.5, 1., 1.5, 2., 3., 4., 6.];
+    const NOISE: [f64; 8] = [0.01, -0.02, 0.015, 0., -0.01, 0.02, -0.015, 0.005];
+
+    /// The residuals of y = a·exp(b·t) on noisy data from y = 3·exp(-0.7·t).
+    fn func(p: &[f64]) -> Vec<f64> {
+        TS.iter()
+            .zip(&NOISE)
+            .map(|(t, noise)| p[0] * (p[1] * t).exp() - 3. * (-0.7 * t).exp() - noise)
+            .collect()
+    }
+
+    fn jacobian(p: &[f64]) -> Matrix<f64> {
+        Matrix::from_fn(TS.len(), 2, |i, j| {
+            let e = (p[1] * TS[i]).exp();
+            if j == 0 {
+                e
+            } else {
+                p[0] * TS[i] * e
+            }
+        })
+    }
+
+    #[test]
+    fn methods_agree() {
+        let damped = LeastSquares::new(vec![1., 0.], func, jacobian)
+            .solve(&Criteria::default())
+            .unwrap();
+        let undamped = LeastSquares::new(vec![2.5, -0.5], func, jacobian)
+            .with_method(LeastSquaresMethod::GaussNewton)
+            .solve(&Criteria::default())
+            .unwrap();
+
+        for (a, b) in damped.parameters.iter().zip(&undamped.parameters) {
+            assert!((a - b).abs() < 1E-10);
+        }
+        assert!((damped.parameters[0] - 3.).abs() < 0.05);
+        assert!((damped.parameters[1] + 0.7).abs() < 0.05);
+        assert!(damped.sum_of_squares > 0. && damped.sum_of_squares < 2E-3);
+
+        // The gradient of the sum of squares vanishes at the solution.
+        let gradient = jacobian(&damped.parameters)
+            .transpose()
+            .mul_vec(&damped.residuals);
+        assert!(gradient.iter().all(|g| g.abs() < 1E-10));
+    }
+
+    #[test]
+    fn damping_handles_rosenbrock() {
+        let rosenbrock = |p: &[f64]| vec![10. * (p[1] - p[0] * p[0]), 1. - p[0]];
+        let jacobian = |p: &[f64]| Matrix::from_rows(&[[-20. * p[0], 10.], [-1., 0.]]);
+
+        let solution = LeastSquares::new(vec![-1.2, 1.], rosenbrock, jacobian)
+            .with_method(LeastSquaresMethod::LevenbergMarquardt(1.))
+            .solve(&Criteria::default())
+            .unwrap();
+        assert!((solution.parameters[0] - 1.).abs() < 1E-12);
+        assert!((solution.parameters[1] - 1.).abs() < 1E-12);
+        assert!(solution.sum_of_squares < 1E-24);
+    }
+
+    #[test]
+    fn reports_failures() {
+        let underdetermined = LeastSquares::new(
+            vec![0., 0.],
+            |p: &[f64]| vec![p[0] + p[1] - 1.],
+            |_: &[f64]| Matrix::from_rows(&[[1., 1.]]),
+        )
+        .solve(&Criteria::default());
+        assert_eq!(
+            underdetermined,
+            Err(NewtonError::DimensionMismatch {
+                expected: 2,
+                found: 1
+            })
+        );
+        let wrong_jacobian = LeastSquares::new(
+            vec![0., 0.],
+            |p: &[f64]| vec![p[0] - 1., p[1] - 2., p[0] * p[1]],
+            |_: &[f64]| Matrix::identity(2),
+        )
+        .solve(&Criteria::default());
+        assert_eq!(
+            wrong_jacobian,
+            Err(NewtonError::DimensionMismatch {
+                expected: 3,
+                found: 2
+            })
+        );
+
+        // A Jacobian of the wrong sign makes every step increase the sum of squares.
+        let inconsistent = LeastSquares::new(
+            vec![0., 0.],
+            |p: &[f64]| vec![p[0] - 1., p[1] - 2.],
+            |_: &[f64]| Matrix::from_rows(&[[-1., 0.], [0., -1.]]),
+        )
+        .solve(&Criteria::default());
+        assert_eq!(
+            inconsistent,
+            Err(NewtonError::LineSearchFailed { at: vec![0., 0.] })
+        );
+    }
+}
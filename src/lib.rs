@@ -1,6 +1,69 @@
 use std::iter::Iterator;
 use std::ops::{Div, Sub};
 
+mod all_roots;
+mod bfgs;
+mod bracketed;
+mod broyden;
+mod complex;
+mod cubic;
+mod damped;
+mod dogleg;
+mod dual;
+mod fit;
+mod fractal;
+mod householder;
+mod interior_point;
+mod krylov;
+mod least_squares;
+mod linalg;
+mod minimize;
+mod newton_cg;
+mod optimize;
+mod polynomial;
+mod real;
+mod secant;
+mod semismooth;
+mod solve;
+mod sqp;
+mod step;
+mod system;
+mod wolfe;
+
+pub use all_roots::{AllRoots, AllRootsMethod, Roots};
+pub use bfgs::{Bfgs, Lbfgs};
+pub use bracketed::BracketedNewton;
+pub use broyden::{Broyden, BroydenUpdate, InitialJacobian};
+pub use complex::Complex;
+pub use cubic::{AdaptiveCubic, CubicOptimum, CubicSubproblem};
+pub use damped::{DampedNewton, DampedStep, LineSearch};
+pub use dogleg::{Dogleg, DoglegStep};
+pub use dual::Dual;
+pub use fit::{curve_fit, CurveFit, Fit};
+pub use fractal::{Basins, NewtonFractal, Pixel};
+pub use householder::{Halley, Householder};
+pub use interior_point::{linear_program, quadratic_program, InteriorPoint, InteriorPointSolution};
+pub use krylov::{Forcing, NewtonKrylov};
+pub use least_squares::{LeastSquares, LeastSquaresMethod, LeastSquaresSolution};
+pub use linalg::{
+    Cholesky, CholeskySolver, Gmres, GmresSolution, LinearSolver, Lu, LuSolver, Matrix,
+    Preconditioner, Qr, QrSolver, SymmetricEigen,
+};
+pub use minimize::{Extremum, ExtremumKind, NewtonMinimize};
+pub use newton_cg::{Globalization, HessianProduct, NewtonCg};
+pub use optimize::{NewtonOptimizer, Optimum};
+pub use polynomial::Polynomial;
+pub use real::Real;
+pub use secant::{Secant, Steffensen};
+pub use semismooth::{Reformulation, SemismoothNewton};
+pub use solve::{Criteria, NewtonError, Solution};
+pub use sqp::{ConstrainedOptimum, Merit, Sqp};
+pub use step::{Step, Steps};
+pub use system::NewtonSystem;
+pub use wolfe::Wolfe;
+
+use solve::{check_derivative, solve_with};
+
 /// An iterator that returns successive iterations of the Newton's method.
 ///
 /// This iterator is generic over it's entry, and allows to freely use the Newton method on
@@ -49,6 +112,70 @@ where
             derivative,
         }
     }
+
+    /// Returns an iterator over the full [`Step`]s, instead of only the iterates.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use generic_newton::Newton;
+    ///
+    /// let mut n = Newton::new(3., |x: f64| x * x - 2., |x| 2. * x);
+    /// let step = n.steps().next().unwrap();
+    ///
+    /// assert_eq!(step.value, 7.);
+    /// assert_eq!(step.derivative, 6.);
+    /// assert_eq!(step.next, 3. - 7. / 6.);
+    /// ```
+    pub fn steps(&mut self) -> Steps<'_, T, F, DF> {
+        Steps { newton: self }
+    }
+
+    fn advance(&mut self) -> Step<T> {
+        let func = &self.func;
+        let deriv = &self.derivative;
+
+        let previous = self.current;
+        let value = func(previous);
+        let derivative = deriv(previous);
+        let step = value / derivative;
+        let next = previous - step;
+
+        self.current = next;
+        Step {
+            previous,
+            value,
+            derivative,
+            step,
+            next,
+        }
+    }
+
+    /// Iterates until `criteria` are met, and returns the root found.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use generic_newton::{Criteria, Newton};
+    ///
+    /// let solution = Newton::new(0.5, |x: f64| x.cos() - x.powi(3), |x| -(x.sin() + 3. * x.powi(2)))
+    ///     .solve(&Criteria::default())
+    ///     .unwrap();
+    ///
+    /// assert!((solution.root - 0.865474033102).abs() < 1E-11);
+    /// assert!(solution.iterations < 10);
+    /// ```
+    pub fn solve(self, criteria: &Criteria<T>) -> Result<Solution<T>, NewtonError<T>>
+    where
+        T: Real,
+    {
+        let func = &self.func;
+        let deriv = &self.derivative;
+
+        solve_with(criteria, self.current, func, |x, fx| {
+            Ok(x - fx / check_derivative(x, deriv(x))?)
+        })
+    }
 }
 
 impl<T, F, DF> Iterator for Newton<T, F, DF>
@@ -59,20 +186,14 @@ where
 {
     type Item = T;
     fn next(&mut self) -> Option<Self::Item> {
-        let func = &self.func;
-        let deriv = &self.derivative;
-
-        let next = self.current - (func(self.current) / deriv(self.current));
-
-        self.current = next;
-        Some(next)
+        Some(self.advance().next)
     }
 }
 
 #[cfg(test)]
 mod tests {
 
-    use super::Newton;
+    use super::{Criteria, Newton, NewtonError};
 
     #[test]
     fn is_generic() {
@@ -86,4 +207,91 @@ mod tests {
         let mut n = Newton::new(0., |x| x - value, |_| 1.);
         assert_eq!(n.nth(5).unwrap(), 1.);
     }
+
+    #[test]
+    fn steps_match_iterates() {
+        let iterates: Vec<f64> = Newton::new(
+            0.5,
+            |x: f64| x.cos() - x.powi(3),
+            |x| -(x.sin() + 3. * x.powi(2)),
+        )
+        .take(5)
+        .collect();
+
+        let mut n = Newton::new(
+            0.5,
+            |x: f64| x.cos() - x.powi(3),
+            |x| -(x.sin() + 3. * x.powi(2)),
+        );
+        let steps: Vec<_> = n.steps().take(5).collect();
+
+        assert_eq!(steps[0].previous, 0.5);
+        for (step, iterate) in steps.iter().zip(iterates) {
+            assert_eq!(step.next, iterate);
+            assert_eq!(step.next, step.previous - step.step);
+        }
+        for pair in steps.windows(2) {
+            assert_eq!(pair[1].previous, pair[0].next);
+        }
+        assert_eq!(
+            n.next(),
+            Newton::new(
+                steps[4].next,
+                |x: f64| x.cos() - x.powi(3),
+                |x| { -(x.sin() + 3. * x.powi(2)) }
+            )
+            .next()
+        );
+    }
+
+    #[test]
+    fn solve_stops_on_step() {
+        let solution = Newton::new(3., |x: f64| x * x - 2., |x| 2. * x)
+            .solve(&Criteria::default())
+            .unwrap();
+        assert!((solution.root - 2f64.sqrt()).abs() < 1E-15);
+        assert!(solution.residual.abs() < 1E-15);
+        assert!(solution.iterations < 10);
+    }
+
+    #[test]
+    fn solve_stops_on_residual() {
+        let criteria = Criteria {
+            residual_tolerance: 1E-3,
+            ..Criteria::default()
+        };
+        let solution = Newton::new(3., |x: f64| x * x - 2., |x| 2. * x)
+            .solve(&criteria)
+            .unwrap();
+        assert!(solution.residual.abs() <= 1E-3);
+    }
+
+    #[test]
+    fn solve_errors() {
+        let criteria = Criteria {
+            max_iterations: 5,
+            ..Criteria::default()
+        };
+
+        assert_eq!(
+            Newton::new(0., |x: f64| x * x + 1., |x| 2. * x).solve(&criteria),
+            Err(NewtonError::ZeroDerivative { at: 0. })
+        );
+        assert_eq!(
+            Newton::new(1., |x: f64| x * x + 1., |_| f64::INFINITY).solve(&criteria),
+            Err(NewtonError::NonFiniteDerivative { at: 1. })
+        );
+        assert_eq!(
+            Newton::new(-1., |x: f64| x.sqrt(), |_| 1.).solve(&criteria),
+            Err(NewtonError::NanResidual { at: -1. })
+        );
+        assert_eq!(
+            Newton::new(1., |x: f64| x * x + 1., |_| 1E-320).solve(&criteria),
+            Err(NewtonError::Divergence { at: 1. })
+        );
+        assert!(matches!(
+            Newton::new(0.5, |x: f64| x * x + 1., |x| 2. * x).solve(&criteria),
+            Err(NewtonError::MaxIterations { iterations: 5, .. })
+        ));
+    }
 }
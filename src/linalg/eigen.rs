@@ -0,0 +1,130 @@
+use super::Matrix;
+use crate::Real;
+
+/// The eigendecomposition `A = Q·Λ·Qᵀ` of a symmetric matrix, computed by the cyclic Jacobi
+/// method.
+///
+/// Jacobi rotations are slower than the tridiagonal QR algorithm, but accurate even for the
+/// small eigenvalues, and simple enough for the small matrices the optimisers need.
+#[derive(Debug, Clone)]
+pub struct SymmetricEigen<T> {
+    /// The eigenvalues, in increasing order.
+    values: Vec<T>,
+    /// The orthonormal eigenvectors, as columns, in the same order.
+    vectors: Matrix<T>,
+}
+
+impl<T: Real> SymmetricEigen<T> {
+    /// Decomposes `matrix`, which is assumed to be symmetric.
+    ///
+    /// # Panics
+    ///
+    /// If `matrix` is not square.
+    pub fn new(matrix: &Matrix<T>) -> Self {
+        let n = matrix.rows();
+        assert_eq!(n, matrix.cols(), "matrix must be square");
+
+        let mut a = matrix.clone();
+        let mut vectors = Matrix::identity(n);
+        let total = (0..n).fold(T::zero(), |acc, row| {
+            (0..n).fold(acc, |acc, col| acc + a[(row, col)] * a[(row, col)])
+        });
+
+        for _ in 0..100 {
+            let off_diagonal = (0..n).fold(T::zero(), |acc, row| {
+                (0..n)
+                    .filter(|&col| col != row)
+                    .fold(acc, |acc, col| acc + a[(row, col)] * a[(row, col)])
+            });
+            if off_diagonal <= T::epsilon() * T::epsilon() * total {
+                break;
+            }
+
+            for p in 0..n {
+                for q in p + 1..n {
+                    if a[(p, q)] == T::zero() {
+                        continue;
+                    }
+                    // The rotation zeroing `a[(p, q)]`.
+                    let theta = (a[(q, q)] - a[(p, p)]) / (a[(p, q)] + a[(p, q)]);
+                    let t = T::one() / (theta.abs() + (theta * theta + T::one()).sqrt());
+                    let t = if theta < T::zero() { -t } else { t };
+                    let c = T::one() / (t * t + T::one()).sqrt();
+                    let s = t * c;
+
+                    for k in 0..n {
+                        let (akp, akq) = (a[(k, p)], a[(k, q)]);
+                        a[(k, p)] = c * akp - s * akq;
+                        a[(k, q)] = s * akp + c * akq;
+                    }
+                    for k in 0..n {
+                        let (apk, aqk) = (a[(p, k)], a[(q, k)]);
+                        a[(p, k)] = c * apk - s * aqk;
+                        a[(q, k)] = s * apk + c * aqk;
+                    }
+                    for k in 0..n {
+                        let (vkp, vkq) = (vectors[(k, p)], vectors[(k, q)]);
+                        vectors[(k, p)] = c * vkp - s * vkq;
+                        vectors[(k, q)] = s * vkp + c * vkq;
+                    }
+                }
+            }
+        }
+
+        let mut order: Vec<usize> = (0..n).collect();
+        order.sort_by(|&i, &j| {
+            a[(i, i)]
+                .partial_cmp(&a[(j, j)])
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        SymmetricEigen {
+            values: order.iter().map(|&i| a[(i, i)]).collect(),
+            vectors: Matrix::from_fn(n, n, |row, col| vectors[(row, order[col])]),
+        }
+    }
+
+    /// The eigenvalues, in increasing order.
+    pub fn values(&self) -> &[T] {
+        &self.values
+    }
+
+    /// The orthonormal eigenvectors, as the columns of a matrix, in the order of
+    /// [`values`](Self::values).
+    pub fn vectors(&self) -> &Matrix<T> {
+        &self.vectors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::SymmetricEigen;
+    use crate::linalg::Matrix;
+
+    #[test]
+    fn decomposes() {
+        let a = Matrix::from_rows(&[[2f64, -1., 0.], [-1., 2., -1.], [0., -1., 2.]]);
+        let eigen = SymmetricEigen::new(&a);
+
+        // The eigenvalues are 2 - √2, 2 and 2 + √2.
+        let root = 2f64.sqrt();
+        for (value, expected) in eigen.values().iter().zip(&[2. - root, 2., 2. + root]) {
+            assert!((value - expected).abs() < 1E-14);
+        }
+
+        let q = eigen.vectors();
+        for (col, &value) in eigen.values().iter().enumerate() {
+            let v: Vec<f64> = (0..3).map(|row| q[(row, col)]).collect();
+            for (av, v) in a.mul_vec(&v).iter().zip(&v) {
+                assert!((av - value * v).abs() < 1E-14);
+            }
+        }
+        let identity = q.transpose().mul_mat(q);
+        for row in 0..3 {
+            for col in 0..3 {
+                let expected = if row == col { 1. } else { 0. };
+                assert!((identity[(row, col)] - expected).abs() < 1E-14);
+            }
+        }
+    }
+}
@@ -0,0 +1,206 @@
+use super::{dot, norm, Preconditioner};
+use crate::Real;
+
+/// The restarted GMRES iterative method for non-symmetric linear systems.
+///
+/// Only products of the matrix with vectors are needed, which makes it suitable when the matrix is
+/// too large to be formed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Gmres<T> {
+    /// The size of the Krylov subspace before restarting.
+    pub restart: usize,
+    /// The maximum total number of products with the matrix.
+    pub max_iterations: usize,
+    /// The iteration stops once `‖b - A·x‖ <= tolerance · ‖b‖`.
+    pub tolerance: T,
+}
+
+impl<T: Real> Default for Gmres<T> {
+    fn default() -> Self {
+        Gmres {
+            restart: 30,
+            max_iterations: 300,
+            tolerance: T::from_f64(1E-10),
+        }
+    }
+}
+
+/// The result of [`Gmres::solve`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct GmresSolution<T> {
+    /// The approximate solution.
+    pub solution: Vec<T>,
+    /// The norm of the residual `b - A·x`.
+    pub residual_norm: T,
+    /// The number of products with the matrix.
+    pub iterations: usize,
+    /// Whether the tolerance was reached.
+    pub converged: bool,
+}
+
+impl<T: Real> Gmres<T> {
+    /// Solves `A·x = rhs`, starting from zero, where `operator(v)` computes `A·v`.
+    ///
+    /// When given, `preconditioner(v)` should approximate `A⁻¹·v`, and is applied on the right so
+    /// that the residuals are the ones of the original system.
+    pub fn solve<A>(
+        &self,
+        operator: A,
+        preconditioner: Option<&Preconditioner<'_, T>>,
+        rhs: &[T],
+    ) -> GmresSolution<T>
+    where
+        A: Fn(&[T]) -> Vec<T>,
+    {
+        let n = rhs.len();
+        let precondition = |v: &[T]| match preconditioner {
+            Some(preconditioner) => preconditioner(v),
+            None => v.to_vec(),
+        };
+
+        let mut solution = vec![T::zero(); n];
+        let target = self.tolerance * norm(rhs);
+        let mut residual = rhs.to_vec();
+        let mut residual_norm = norm(&residual);
+        let mut iterations = 0;
+        let restart = self.restart.max(1);
+
+        while residual_norm > target && iterations < self.max_iterations {
+            let mut basis = vec![scaled(&residual, T::one() / residual_norm)];
+            let mut hessenberg: Vec<Vec<T>> = Vec::with_capacity(restart);
+            let mut rotations: Vec<(T, T)> = Vec::with_capacity(restart);
+            let mut g = vec![residual_norm];
+
+            for j in 0..restart {
+                let mut w = operator(&precondition(&basis[j]));
+                let mut column = Vec::with_capacity(j + 2);
+                for v in &basis {
+                    let h = dot(&w, v);
+                    for (w, &v) in w.iter_mut().zip(v) {
+                        *w = *w - h * v;
+                    }
+                    column.push(h);
+                }
+                let next_norm = norm(&w);
+                column.push(next_norm);
+
+                for (i, &(c, s)) in rotations.iter().enumerate() {
+                    let (a, b) = (column[i], column[i + 1]);
+                    column[i] = c * a + s * b;
+                    column[i + 1] = c * b - s * a;
+                }
+                let (a, b) = (column[j], column[j + 1]);
+                let radius = (a * a + b * b).sqrt();
+                let (c, s) = if radius == T::zero() {
+                    (T::one(), T::zero())
+                } else {
+                    (a / radius, b / radius)
+                };
+                column[j] = radius;
+                column[j + 1] = T::zero();
+                rotations.push((c, s));
+                g.push(-s * g[j]);
+                g[j] = c * g[j];
+
+                hessenberg.push(column);
+                iterations += 1;
+
+                if g[j + 1].abs() <= target
+                    || iterations >= self.max_iterations
+                    || next_norm == T::zero()
+                {
+                    break;
+                }
+                basis.push(scaled(&w, T::one() / next_norm));
+            }
+
+            // Back substitution on the triangular factor, stored column by column.
+            let k = hessenberg.len();
+            let mut y = vec![T::zero(); k];
+            for i in (0..k).rev() {
+                let mut sum = g[i];
+                for (l, &yl) in y.iter().enumerate().skip(i + 1) {
+                    sum = sum - hessenberg[l][i] * yl;
+                }
+                y[i] = if hessenberg[i][i] == T::zero() {
+                    T::zero()
+                } else {
+                    sum / hessenberg[i][i]
+                };
+            }
+
+            let mut combination = vec![T::zero(); n];
+            for (v, &y) in basis.iter().zip(&y) {
+                for (c, &v) in combination.iter_mut().zip(v) {
+                    *c = *c + y * v;
+                }
+            }
+            for (x, u) in solution.iter_mut().zip(precondition(&combination)) {
+                *x = *x + u;
+            }
+
+            let product = operator(&solution);
+            residual = rhs.iter().zip(&product).map(|(&b, &p)| b - p).collect();
+            let new_norm = norm(&residual);
+            if new_norm >= residual_norm {
+                // No progress can be made, the Krylov subspace is exhausted.
+                residual_norm = new_norm;
+                break;
+            }
+            residual_norm = new_norm;
+        }
+
+        GmresSolution {
+            solution,
+            residual_norm,
+            iterations,
+            converged: residual_norm <= target,
+        }
+    }
+}
+
+/// Multiplies a vector by a scalar.
+fn scaled<T: Real>(v: &[T], factor: T) -> Vec<T> {
+    v.iter().map(|&x| x * factor).collect()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::Gmres;
+    use crate::linalg::Matrix;
+
+    #[test]
+    fn solves_nonsymmetric() {
+        let n = 20;
+        let a = Matrix::from_fn(n, n, |row, col| {
+            if row == col {
+                4.
+            } else if col == row + 1 {
+                -1.
+            } else if row == col + 1 {
+                -2.
+            } else {
+                0.
+            }
+        });
+        let expected: Vec<f64> = (0..n).map(|i| i as f64).collect();
+        let rhs = a.mul_vec(&expected);
+
+        let gmres = Gmres {
+            restart: 5,
+            ..Gmres::default()
+        };
+        let result = gmres.solve(|v| a.mul_vec(v), None, &rhs);
+        assert!(result.converged);
+        for (x, e) in result.solution.iter().zip(&expected) {
+            assert!((x - e).abs() < 1E-8);
+        }
+
+        // The exact inverse of the diagonal makes convergence faster.
+        let jacobi = |v: &[f64]| v.iter().map(|x| x / 4.).collect();
+        let preconditioned = gmres.solve(|v| a.mul_vec(v), Some(&jacobi), &rhs);
+        assert!(preconditioned.converged);
+        assert!(preconditioned.iterations <= result.iterations);
+    }
+}
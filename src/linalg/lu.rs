@@ -0,0 +1,145 @@
+use super::{LinearSolver, Matrix};
+use crate::Real;
+
+/// The LU decomposition with partial pivoting `P·A = L·U` of a square matrix.
+///
+/// Once computed, it can solve several systems with the same matrix.
+#[derive(Debug, Clone)]
+pub struct Lu<T> {
+    /// `L` below the diagonal, with an implicit unit diagonal, and `U` above.
+    factors: Matrix<T>,
+    /// The row of `A` used as the `i`-th row of the factors.
+    permutation: Vec<usize>,
+}
+
+impl<T: Real> Lu<T> {
+    /// Decomposes `matrix`, returning `None` if it is singular.
+    ///
+    /// # Panics
+    ///
+    /// If `matrix` is not square.
+    pub fn new(matrix: &Matrix<T>) -> Option<Self> {
+        let n = matrix.rows();
+        assert_eq!(n, matrix.cols(), "matrix must be square");
+
+        let mut factors = matrix.clone();
+        let mut permutation: Vec<usize> = (0..n).collect();
+
+        for k in 0..n {
+            let pivot = (k..n)
+                .max_by(|&i, &j| {
+                    factors[(i, k)]
+                        .abs()
+                        .partial_cmp(&factors[(j, k)].abs())
+                        .unwrap_or(std::cmp::Ordering::Equal)
+                })
+                .expect("the range is not empty");
+
+            if factors[(pivot, k)] == T::zero() || !factors[(pivot, k)].is_finite() {
+                return None;
+            }
+
+            if pivot != k {
+                permutation.swap(pivot, k);
+                for col in 0..n {
+                    let tmp = factors[(pivot, col)];
+                    factors[(pivot, col)] = factors[(k, col)];
+                    factors[(k, col)] = tmp;
+                }
+            }
+
+            for row in k + 1..n {
+                let factor = factors[(row, k)] / factors[(k, k)];
+                factors[(row, k)] = factor;
+                for col in k + 1..n {
+                    factors[(row, col)] = factors[(row, col)] - factor * factors[(k, col)];
+                }
+            }
+        }
+
+        Some(Lu {
+            factors,
+            permutation,
+        })
+    }
+
+    /// Solves `A·x = rhs`.
+    ///
+    /// # Panics
+    ///
+    /// If the length of `rhs` is not the size of the matrix.
+    pub fn solve(&self, rhs: &[T]) -> Vec<T> {
+        let n = self.factors.rows();
+        assert_eq!(rhs.len(), n, "dimension mismatch");
+
+        let mut x: Vec<T> = self.permutation.iter().map(|&i| rhs[i]).collect();
+        for row in 0..n {
+            for col in 0..row {
+                x[row] = x[row] - self.factors[(row, col)] * x[col];
+            }
+        }
+        for row in (0..n).rev() {
+            for col in row + 1..n {
+                x[row] = x[row] - self.factors[(row, col)] * x[col];
+            }
+            x[row] = x[row] / self.factors[(row, row)];
+        }
+        x
+    }
+
+    /// Computes `A⁻¹`.
+    pub fn inverse(&self) -> Matrix<T> {
+        let n = self.factors.rows();
+        let mut inverse = Matrix::zeros(n, n);
+        let mut unit = vec![T::zero(); n];
+        for col in 0..n {
+            unit[col] = T::one();
+            for (row, x) in self.solve(&unit).into_iter().enumerate() {
+                inverse[(row, col)] = x;
+            }
+            unit[col] = T::zero();
+        }
+        inverse
+    }
+}
+
+/// The default [`LinearSolver`], using a dense [`Lu`] decomposition.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LuSolver;
+
+impl<T: Real> LinearSolver<T> for LuSolver {
+    fn solve(&mut self, matrix: &Matrix<T>, rhs: &[T]) -> Option<Vec<T>> {
+        Lu::new(matrix).map(|lu| lu.solve(rhs))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{Lu, LuSolver};
+    use crate::linalg::{LinearSolver, Matrix};
+
+    #[test]
+    fn solves() {
+        // The zero in the corner requires pivoting.
+        let a = Matrix::from_rows(&[[0., 2., 1.], [1., 1., 1.], [2., 1., 3.]]);
+        let x = LuSolver.solve(&a, &[7., 6., 13.]).unwrap();
+        for (xi, expected) in x.iter().zip(&[1f64, 2., 3.]) {
+            assert!((xi - expected).abs() < 1E-14);
+        }
+
+        let product = a.mul_mat(&Lu::new(&a).unwrap().inverse());
+        for row in 0..3 {
+            for col in 0..3 {
+                let expected: f64 = if row == col { 1. } else { 0. };
+                assert!((product[(row, col)] - expected).abs() < 1E-14);
+            }
+        }
+    }
+
+    #[test]
+    fn singular() {
+        let a = Matrix::from_rows(&[[1., 2.], [2., 4.]]);
+        assert!(LuSolver.solve(&a, &[1., 1.]).is_none());
+    }
+}
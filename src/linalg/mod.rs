@@ -0,0 +1,177 @@
+//! Dense linear algebra used by the solvers for systems of equations.
+
+use std::ops::{Index, IndexMut};
+
+use crate::Real;
+
+mod cholesky;
+mod eigen;
+mod gmres;
+mod lu;
+mod qr;
+
+pub use cholesky::{Cholesky, CholeskySolver};
+pub use eigen::SymmetricEigen;
+pub use gmres::{Gmres, GmresSolution};
+pub use lu::{Lu, LuSolver};
+pub use qr::{Qr, QrSolver};
+
+/// A dense matrix, stored row by row.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Matrix<T> {
+    rows: usize,
+    cols: usize,
+    data: Vec<T>,
+}
+
+impl<T: Real> Matrix<T> {
+    /// Creates a matrix whose entries are computed by `entry(row, col)`.
+    pub fn from_fn<E: FnMut(usize, usize) -> T>(rows: usize, cols: usize, mut entry: E) -> Self {
+        let mut data = Vec::with_capacity(rows * cols);
+        for row in 0..rows {
+            for col in 0..cols {
+                data.push(entry(row, col));
+            }
+        }
+        Matrix { rows, cols, data }
+    }
+
+    /// Creates a matrix from a list of rows.
+    ///
+    /// # Panics
+    ///
+    /// If the rows do not all have the same length.
+    pub fn from_rows<R: AsRef<[T]>>(rows: &[R]) -> Self {
+        let cols = rows.first().map_or(0, |row| row.as_ref().len());
+        let mut data = Vec::with_capacity(rows.len() * cols);
+        for row in rows {
+            assert_eq!(row.as_ref().len(), cols, "rows must have the same length");
+            data.extend_from_slice(row.as_ref());
+        }
+        Matrix {
+            rows: rows.len(),
+            cols,
+            data,
+        }
+    }
+
+    /// Creates a matrix filled with zeros.
+    pub fn zeros(rows: usize, cols: usize) -> Self {
+        Matrix {
+            rows,
+            cols,
+            data: vec![T::zero(); rows * cols],
+        }
+    }
+
+    /// Creates the identity matrix of size `n`.
+    pub fn identity(n: usize) -> Self {
+        Matrix::from_fn(
+            n,
+            n,
+            |row, col| if row == col { T::one() } else { T::zero() },
+        )
+    }
+
+    /// The number of rows.
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// The number of columns.
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// The entries of the `row`-th row.
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.data[row * self.cols..(row + 1) * self.cols]
+    }
+
+    /// Whether every entry is finite.
+    pub fn is_finite(&self) -> bool {
+        self.data.iter().all(|x| x.is_finite())
+    }
+
+    /// The transposed matrix.
+    pub fn transpose(&self) -> Self {
+        Matrix::from_fn(self.cols, self.rows, |row, col| self[(col, row)])
+    }
+
+    /// Multiplies this matrix by the vector `vector`.
+    ///
+    /// # Panics
+    ///
+    /// If the length of `vector` is not the number of columns.
+    pub fn mul_vec(&self, vector: &[T]) -> Vec<T> {
+        assert_eq!(vector.len(), self.cols, "dimension mismatch");
+        (0..self.rows)
+            .map(|row| dot(self.row(row), vector))
+            .collect()
+    }
+
+    /// Multiplies this matrix by `other`.
+    ///
+    /// # Panics
+    ///
+    /// If the number of columns is not the number of rows of `other`.
+    pub fn mul_mat(&self, other: &Matrix<T>) -> Self {
+        assert_eq!(self.cols, other.rows, "dimension mismatch");
+        Matrix::from_fn(self.rows, other.cols, |row, col| {
+            (0..self.cols).fold(T::zero(), |acc, k| acc + self[(row, k)] * other[(k, col)])
+        })
+    }
+}
+
+impl<T> Index<(usize, usize)> for Matrix<T> {
+    type Output = T;
+    fn index(&self, (row, col): (usize, usize)) -> &T {
+        assert!(row < self.rows && col < self.cols, "index out of bounds");
+        &self.data[row * self.cols + col]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Matrix<T> {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut T {
+        assert!(row < self.rows && col < self.cols, "index out of bounds");
+        &mut self.data[row * self.cols + col]
+    }
+}
+
+/// A method to solve square linear systems `A·x = b`.
+pub trait LinearSolver<T> {
+    /// Solves `matrix · x = rhs`, returning `None` if `matrix` is singular.
+    fn solve(&mut self, matrix: &Matrix<T>, rhs: &[T]) -> Option<Vec<T>>;
+}
+
+/// A function approximating the product of the inverse of a matrix with a vector.
+pub type Preconditioner<'a, T> = dyn Fn(&[T]) -> Vec<T> + 'a;
+
+/// The dot product of two vectors.
+pub(crate) fn dot<T: Real>(a: &[T], b: &[T]) -> T {
+    a.iter().zip(b).fold(T::zero(), |acc, (&x, &y)| acc + x * y)
+}
+
+/// The euclidean norm of a vector.
+pub(crate) fn norm<T: Real>(a: &[T]) -> T {
+    dot(a, a).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::Matrix;
+
+    #[test]
+    fn products() {
+        let a = Matrix::from_rows(&[[1., 2., 3.], [4., 5., 6.]]);
+
+        assert_eq!(a.mul_vec(&[1., 0., -1.]), vec![-2., -2.]);
+        assert_eq!(a.transpose()[(2, 1)], 6.);
+        assert_eq!(
+            a.mul_mat(&a.transpose()),
+            Matrix::from_rows(&[[14., 32.], [32., 77.]])
+        );
+        assert_eq!(a.mul_mat(&Matrix::identity(3)), a);
+    }
+}
@@ -0,0 +1,207 @@
+use super::{LinearSolver, Matrix};
+use crate::Real;
+
+/// The QR decomposition `A = Q·R` of a matrix with at least as many rows as columns, computed with
+/// Householder reflections.
+///
+/// It solves linear least squares problems without forming `Aᵀ·A`, whose condition number is the
+/// square of the one of `A`.
+#[derive(Debug, Clone)]
+pub struct Qr<T> {
+    /// `R` strictly above the diagonal, and the Householder vectors below.
+    factors: Matrix<T>,
+    /// The diagonal of `R`.
+    diagonal: Vec<T>,
+    /// The scaling of each Householder reflection.
+    betas: Vec<T>,
+}
+
+impl<T: Real> Qr<T> {
+    /// Decomposes `matrix`.
+    ///
+    /// # Panics
+    ///
+    /// If `matrix` has less rows than columns.
+    pub fn new(matrix: &Matrix<T>) -> Self {
+        let (m, n) = (matrix.rows(), matrix.cols());
+        assert!(m >= n, "matrix must have at least as many rows as columns");
+
+        let mut factors = matrix.clone();
+        let mut diagonal = Vec::with_capacity(n);
+        let mut betas = Vec::with_capacity(n);
+
+        for k in 0..n {
+            let column_norm = (k..m)
+                .fold(T::zero(), |acc, i| acc + factors[(i, k)] * factors[(i, k)])
+                .sqrt();
+            if column_norm == T::zero() {
+                diagonal.push(T::zero());
+                betas.push(T::zero());
+                continue;
+            }
+
+            let alpha = if factors[(k, k)] > T::zero() {
+                -column_norm
+            } else {
+                column_norm
+            };
+            factors[(k, k)] = factors[(k, k)] - alpha;
+            let v_norm = (k..m).fold(T::zero(), |acc, i| acc + factors[(i, k)] * factors[(i, k)]);
+            let beta = T::from_f64(2.) / v_norm;
+
+            for j in k + 1..n {
+                let projection =
+                    (k..m).fold(T::zero(), |acc, i| acc + factors[(i, k)] * factors[(i, j)]);
+                for i in k..m {
+                    factors[(i, j)] = factors[(i, j)] - beta * projection * factors[(i, k)];
+                }
+            }
+
+            diagonal.push(alpha);
+            betas.push(beta);
+        }
+
+        Qr {
+            factors,
+            diagonal,
+            betas,
+        }
+    }
+
+    /// Whether `R` has a negligible diagonal entry, in which case `A` does not have full column
+    /// rank.
+    pub fn is_rank_deficient(&self) -> bool {
+        let largest =
+            self.diagonal.iter().fold(
+                T::zero(),
+                |acc, &d| if d.abs() > acc { d.abs() } else { acc },
+            );
+        let size = self.factors.rows().max(self.factors.cols()) as f64;
+        let threshold = T::from_f64(size) * T::epsilon() * largest;
+        largest == T::zero() || self.diagonal.iter().any(|d| d.abs() <= threshold)
+    }
+
+    /// Computes `Qᵀ·rhs`.
+    ///
+    /// # Panics
+    ///
+    /// If the length of `rhs` is not the number of rows.
+    pub fn q_transpose_mul(&self, rhs: &[T]) -> Vec<T> {
+        let m = self.factors.rows();
+        assert_eq!(rhs.len(), m, "dimension mismatch");
+
+        let mut result = rhs.to_vec();
+        for (k, &beta) in self.betas.iter().enumerate() {
+            let projection =
+                (k..m).fold(T::zero(), |acc, i| acc + self.factors[(i, k)] * result[i]);
+            for (i, r) in result.iter_mut().enumerate().skip(k) {
+                *r = *r - beta * projection * self.factors[(i, k)];
+            }
+        }
+        result
+    }
+
+    /// Solves `R·x = rhs` for the upper triangular `R`, where `rhs` has one entry per column.
+    fn solve_r(&self, rhs: &[T]) -> Vec<T> {
+        let n = self.factors.cols();
+        let mut x = rhs[..n].to_vec();
+        for row in (0..n).rev() {
+            for col in row + 1..n {
+                x[row] = x[row] - self.factors[(row, col)] * x[col];
+            }
+            x[row] = x[row] / self.diagonal[row];
+        }
+        x
+    }
+
+    /// Finds the `x` minimising `‖A·x - rhs‖`, returning `None` if `A` is rank deficient.
+    ///
+    /// # Panics
+    ///
+    /// If the length of `rhs` is not the number of rows.
+    pub fn solve_least_squares(&self, rhs: &[T]) -> Option<Vec<T>> {
+        if self.is_rank_deficient() {
+            return None;
+        }
+        Some(self.solve_r(&self.q_transpose_mul(rhs)))
+    }
+
+    /// Computes `(Aᵀ·A)⁻¹ = R⁻¹·R⁻ᵀ`, returning `None` if `A` is rank deficient.
+    pub fn normal_inverse(&self) -> Option<Matrix<T>> {
+        if self.is_rank_deficient() {
+            return None;
+        }
+
+        let n = self.factors.cols();
+        let mut inverse = Matrix::zeros(n, n);
+        for col in 0..n {
+            // Solves Rᵀ·z = e_col, then R·x = z.
+            let mut z = vec![T::zero(); n];
+            z[col] = T::one();
+            for row in 0..n {
+                for k in 0..row {
+                    z[row] = z[row] - self.factors[(k, row)] * z[k];
+                }
+                z[row] = z[row] / self.diagonal[row];
+            }
+            for (row, x) in self.solve_r(&z).into_iter().enumerate() {
+                inverse[(row, col)] = x;
+            }
+        }
+        Some(inverse)
+    }
+}
+
+/// A [`LinearSolver`] using a [`Qr`] decomposition, which is slower than [`LuSolver`] but more
+/// stable on ill-conditioned matrices.
+///
+/// [`LuSolver`]: super::LuSolver
+#[derive(Debug, Clone, Copy, Default)]
+pub struct QrSolver;
+
+impl<T: Real> LinearSolver<T> for QrSolver {
+    fn solve(&mut self, matrix: &Matrix<T>, rhs: &[T]) -> Option<Vec<T>> {
+        Qr::new(matrix).solve_least_squares(rhs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{Qr, QrSolver};
+    use crate::linalg::{LinearSolver, Matrix};
+
+    #[test]
+    fn least_squares_line() {
+        // Fits y = a + b·x through points on y = 1 + 2x, with symmetric noise.
+        let xs = [0., 1., 2., 3.];
+        let ys = [1.1f64, 2.9, 5.1, 6.9];
+        let a = Matrix::from_fn(4, 2, |row, col| if col == 0 { 1. } else { xs[row] });
+
+        let fit = Qr::new(&a).solve_least_squares(&ys).unwrap();
+        assert!((fit[0] - 1.06).abs() < 1E-12);
+        assert!((fit[1] - 1.96).abs() < 1E-12);
+
+        let inverse = Qr::new(&a).normal_inverse().unwrap();
+        let product = a.transpose().mul_mat(&a).mul_mat(&inverse);
+        for row in 0..2 {
+            for col in 0..2 {
+                let expected = if row == col { 1. } else { 0. };
+                assert!((product[(row, col)] - expected).abs() < 1E-12);
+            }
+        }
+    }
+
+    #[test]
+    fn square_and_deficient() {
+        let a = Matrix::from_rows(&[[0., 2., 1.], [1., 1., 1.], [2., 1., 3.]]);
+        let x = QrSolver.solve(&a, &[7., 6., 13.]).unwrap();
+        for (xi, expected) in x.iter().zip(&[1f64, 2., 3.]) {
+            assert!((xi - expected).abs() < 1E-14);
+        }
+
+        let a = Matrix::from_rows(&[[1., 2.], [2., 4.], [3., 6.]]);
+        assert!(Qr::new(&a).is_rank_deficient());
+        assert!(Qr::new(&a).solve_least_squares(&[1., 1., 1.]).is_none());
+    }
+}
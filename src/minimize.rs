@@ -0,0 +1,372 @@
+use std::iter::Iterator;
+
+use crate::{Criteria, NewtonError, Real};
+
+/// The kind of stationary point found by [`NewtonMinimize`], given by the sign of the second
+/// derivative.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExtremumKind {
+    /// The second derivative is positive.
+    Minimum,
+    /// The second derivative is negative.
+    Maximum,
+    /// The second derivative vanishes.
+    Inflection,
+}
+
+/// The result of [`NewtonMinimize::solve`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Extremum<T> {
+    /// The point found.
+    pub point: T,
+    /// The value of the function at `point`.
+    pub value: T,
+    /// The derivative at `point`.
+    pub derivative: T,
+    /// The second derivative at `point`.
+    pub second_derivative: T,
+    /// What the second derivative says about `point`.
+    pub kind: ExtremumKind,
+    /// The number of iterations performed.
+    pub iterations: usize,
+}
+
+/// `(3 - √5) / 2`, the fraction of an interval a golden-section step moves by.
+const GOLDEN_SECTION: f64 = 0.381_966_011_250_105_1;
+
+/// `(1 + √5) / 2`, the factor successive expansion steps grow by.
+const GOLDEN_RATIO: f64 = 1.618_033_988_749_895;
+
+/// Everything about the iteration that is not the current point.
+struct State<T> {
+    /// The lower and upper ends of the interval known to contain a minimiser, if any.
+    lower: Option<T>,
+    upper: Option<T>,
+    /// The length of the next expansion step.
+    step: T,
+}
+
+impl<T: Real> State<T> {
+    /// Whether `y` lies strictly inside the known interval.
+    // `Option::is_none_or` would need Rust 1.82.
+    #[allow(clippy::unnecessary_map_or)]
+    fn contains(&self, y: T) -> bool {
+        self.lower.map_or(true, |lower| y > lower) && self.upper.map_or(true, |upper| y < upper)
+    }
+
+    /// Records that `y` has a larger value than `x`, so a minimiser lies on the side of `y`
+    /// closer to `x`.
+    fn exclude_beyond(&mut self, x: T, y: T) {
+        if y > x {
+            self.upper = Some(y);
+        } else {
+            self.lower = Some(y);
+        }
+    }
+
+    /// Records a move from `x` to a lower point `y`, so `x` bounds the interval.
+    fn moved(&mut self, x: T, y: T) {
+        if y > x {
+            self.lower = Some(x);
+        } else {
+            self.upper = Some(x);
+        }
+    }
+
+    /// Computes the next iterate from `x`, where `dfx` and `ddfx` are the first and second
+    /// derivatives at `x`.
+    fn advance<F: Fn(T) -> T>(
+        &mut self,
+        func: &F,
+        x: T,
+        dfx: T,
+        ddfx: T,
+    ) -> Result<T, NewtonError<T>> {
+        if !ddfx.is_finite() {
+            return Err(NewtonError::NonFiniteDerivative { at: x });
+        }
+        if dfx == T::zero() && ddfx > T::zero() {
+            return Ok(x);
+        }
+
+        let fx = func(x);
+        if ddfx > T::zero() {
+            let y = x - dfx / ddfx;
+            if self.contains(y) {
+                // Close to a minimiser, rounding can make f(y) and f(x) equal.
+                if func(y) <= fx {
+                    self.moved(x, y);
+                    return Ok(y);
+                }
+                self.exclude_beyond(x, y);
+            }
+        }
+
+        Ok(self.fallback(func, x, fx, dfx))
+    }
+
+    /// Looks for a point lower than `x` downhill, by expansion steps if the interval is unbounded
+    /// on that side and by golden-section steps once it is bounded.
+    ///
+    /// From a stationary point, both sides are searched. `x` is returned if no lower point is
+    /// found before the interval shrinks to nothing.
+    fn fallback<F: Fn(T) -> T>(&mut self, func: &F, x: T, fx: T, dfx: T) -> T {
+        let downhill = if dfx > T::zero() { -T::one() } else { T::one() };
+        let directions = if dfx == T::zero() { 2 } else { 1 };
+        let golden = T::from_f64(GOLDEN_SECTION);
+        let width = T::epsilon() * (T::one() + x.abs());
+
+        for direction in [downhill, -downhill].iter().take(directions) {
+            let mut end = if *direction > T::zero() {
+                self.upper
+            } else {
+                self.lower
+            };
+
+            loop {
+                let y = match end {
+                    Some(end) if (end - x).abs() <= width => break,
+                    Some(end) => x + golden * (end - x),
+                    None => x + *direction * self.step,
+                };
+
+                if func(y) < fx {
+                    if end.is_none() {
+                        self.step = self.step * T::from_f64(GOLDEN_RATIO);
+                    }
+                    self.moved(x, y);
+                    return y;
+                }
+                self.exclude_beyond(x, y);
+                end = Some(y);
+            }
+        }
+        x
+    }
+}
+
+/// An iterator that returns successive iterations of a safeguarded Newton's method for
+/// minimisation.
+///
+/// Newton's method applied to `f'`, using `f''` as its derivative, converges to any stationary
+/// point. Here, a Newton step is only taken when `f''` is positive and the step decreases `f`.
+/// Otherwise, the iteration falls back to expansion steps downhill until an interval containing a
+/// minimiser is found, and to golden-section steps inside that interval, so that `f` decreases at
+/// every iteration.
+///
+/// # Example
+///
+/// ```
+/// use generic_newton::{ExtremumKind, NewtonMinimize};
+///
+/// // f(x) = x⁴ - 3x² + x has a maximum near 0.17, and Newton's method on f' converges to it.
+/// let n = NewtonMinimize::new(
+///     0.3, // Initial guess
+///     |x: f64| x.powi(4) - 3. * x * x + x, // The actual function
+///     |x| 4. * x.powi(3) - 6. * x + 1., // Its derivative
+///     |x| 12. * x * x - 6., // Its second derivative
+/// );
+///
+/// let minimum = n.solve(&Default::default()).unwrap();
+/// assert_eq!(minimum.kind, ExtremumKind::Minimum);
+/// assert!((minimum.point + 1.30084).abs() < 1E-5 || (minimum.point - 1.13090).abs() < 1E-5);
+/// ```
+pub struct NewtonMinimize<T, F, DF, DDF>
+where
+    T: Real,
+    F: Fn(T) -> T,
+    DF: Fn(T) -> T,
+    DDF: Fn(T) -> T,
+{
+    current: T,
+    func: F,
+    derivative: DF,
+    second_derivative: DDF,
+    state: State<T>,
+}
+
+impl<T, F, DF, DDF> NewtonMinimize<T, F, DF, DDF>
+where
+    T: Real,
+    F: Fn(T) -> T,
+    DF: Fn(T) -> T,
+    DDF: Fn(T) -> T,
+{
+    /// Creates a new `NewtonMinimize` iterator, whose first expansion step has length one.
+    ///
+    /// - `func` is the actual function to minimise
+    /// - `derivative` is it's derivative
+    /// - `second_derivative` is it's second derivative.
+    pub fn new(initial_guess: T, func: F, derivative: DF, second_derivative: DDF) -> Self {
+        NewtonMinimize {
+            current: initial_guess,
+            func,
+            derivative,
+            second_derivative,
+            state: State {
+                lower: None,
+                upper: None,
+                step: T::one(),
+            },
+        }
+    }
+
+    /// Restricts the search to the interval between `lower` and `upper`, which should contain the
+    /// initial guess.
+    pub fn with_bracket(mut self, lower: T, upper: T) -> Self {
+        self.state.lower = Some(lower);
+        self.state.upper = Some(upper);
+        self
+    }
+
+    /// Sets the length of the first expansion step.
+    pub fn with_step(mut self, step: T) -> Self {
+        self.state.step = step;
+        self
+    }
+
+    /// Iterates until `criteria` are met, and returns the stationary point found.
+    ///
+    /// The residual tolerance of `criteria` applies to the derivative, where the second derivative
+    /// is positive. The point found is a minimum unless the iteration got stuck, which is reported
+    /// by [`Extremum::kind`].
+    pub fn solve(self, criteria: &Criteria<T>) -> Result<Extremum<T>, NewtonError<T>> {
+        let func = &self.func;
+        let deriv = &self.derivative;
+        let second = &self.second_derivative;
+        let mut state = self.state;
+
+        let mut current = self.current;
+        let mut step_converged = false;
+        let mut iterations = 0;
+
+        // This is `solve_with`, except that a small derivative only stops the iteration at a
+        // minimum, so that it does not stop on a maximum.
+        loop {
+            let dfx = deriv(current);
+            if dfx.is_nan() {
+                return Err(NewtonError::NanResidual { at: current });
+            }
+            let ddfx = second(current);
+
+            if step_converged || (dfx.abs() <= criteria.residual_tolerance && ddfx > T::zero()) {
+                let kind = if ddfx > T::zero() {
+                    ExtremumKind::Minimum
+                } else if ddfx < T::zero() {
+                    ExtremumKind::Maximum
+                } else {
+                    ExtremumKind::Inflection
+                };
+
+                return Ok(Extremum {
+                    point: current,
+                    value: func(current),
+                    derivative: dfx,
+                    second_derivative: ddfx,
+                    kind,
+                    iterations,
+                });
+            }
+
+            if iterations >= criteria.max_iterations {
+                return Err(NewtonError::MaxIterations {
+                    last: current,
+                    iterations,
+                });
+            }
+
+            let next = state.advance(func, current, dfx, ddfx)?;
+            if !next.is_finite() {
+                return Err(NewtonError::Divergence { at: current });
+            }
+
+            step_converged = criteria.step_converged(current, next);
+            current = next;
+            iterations += 1;
+        }
+    }
+}
+
+impl<T, F, DF, DDF> Iterator for NewtonMinimize<T, F, DF, DDF>
+where
+    T: Real,
+    F: Fn(T) -> T,
+    DF: Fn(T) -> T,
+    DDF: Fn(T) -> T,
+{
+    type Item = T;
+    fn next(&mut self) -> Option<Self::Item> {
+        let dfx = (self.derivative)(self.current);
+        let ddfx = (self.second_derivative)(self.current);
+        self.current = self
+            .state
+            .advance(&self.func, self.current, dfx, ddfx)
+            .ok()?;
+        Some(self.current)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{ExtremumKind, NewtonMinimize};
+    use crate::{Criteria, Newton};
+
+    fn f(x: f64) -> f64 {
+        x.powi(4) - 3. * x * x + x
+    }
+
+    fn df(x: f64) -> f64 {
+        4. * x.powi(3) - 6. * x + 1.
+    }
+
+    fn ddf(x: f64) -> f64 {
+        12. * x * x - 6.
+    }
+
+    #[test]
+    fn avoids_maximum() {
+        let criteria = Criteria::default();
+
+        // Plain Newton on the derivative finds the local maximum.
+        let maximum = Newton::new(0.3, df, ddf).solve(&criteria).unwrap();
+        assert!(ddf(maximum.root) < 0.);
+
+        let mut values = vec![f(0.3)];
+        values.extend(NewtonMinimize::new(0.3, f, df, ddf).take(20).map(f));
+        for pair in values.windows(2) {
+            assert!(pair[1] <= pair[0]);
+        }
+
+        let minimum = NewtonMinimize::new(0.3, f, df, ddf)
+            .solve(&criteria)
+            .unwrap();
+        assert_eq!(minimum.kind, ExtremumKind::Minimum);
+        assert!(minimum.derivative.abs() < 1E-12);
+        assert!(minimum.value < f(maximum.root));
+    }
+
+    #[test]
+    fn bracket_and_stationary_start() {
+        // Starting exactly on the maximum, both sides are searched.
+        let minimum = NewtonMinimize::new(0., |x: f64| x.cos(), |x| -x.sin(), |x| -x.cos())
+            .solve(&Criteria::default())
+            .unwrap();
+        assert_eq!(minimum.kind, ExtremumKind::Minimum);
+        assert!((minimum.point.abs() - std::f64::consts::PI).abs() < 1E-12);
+
+        let bracketed = NewtonMinimize::new(-0.5, f, df, ddf)
+            .with_bracket(-2., 0.)
+            .solve(&Criteria::default())
+            .unwrap();
+        assert!((bracketed.point + 1.30084).abs() < 1E-5);
+        assert_eq!(bracketed.kind, ExtremumKind::Minimum);
+
+        // Nothing lower can be found in this interval, so the inflection is reported.
+        let flat = NewtonMinimize::new(0., |x: f64| x.powi(3), |x| 3. * x * x, |x| 6. * x)
+            .with_bracket(-1E-300, 1.)
+            .solve(&Criteria::default())
+            .unwrap();
+        assert_eq!(flat.kind, ExtremumKind::Inflection);
+    }
+}
@@ -0,0 +1,505 @@
+use std::cell::Cell;
+use std::iter::Iterator;
+
+use crate::linalg::{dot, norm};
+use crate::optimize::{failed_search, Advance, Cached};
+use crate::solve::solve_system_with;
+use crate::{Criteria, NewtonError, Optimum, Real, Wolfe};
+
+/// How [`NewtonCg`] turns the truncated Newton directions into steps.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Globalization<T> {
+    /// Searches along the direction for a step satisfying the strong [`Wolfe`] conditions. The
+    /// conjugate gradient iteration stops as soon as it meets negative curvature.
+    LineSearch(Wolfe<T>),
+    /// Restricts the steps to a trust region, starting with the given radius, which is resized
+    /// depending on how well the quadratic model predicted the decrease of the function. The
+    /// conjugate gradient iteration follows Steihaug, and stops on the boundary of the region.
+    TrustRegion(T),
+}
+
+/// A function computing the product of the Hessian at its first argument with its second one.
+pub type HessianProduct<T> = fn(&[T], &[T]) -> Vec<T>;
+
+/// The result of the conjugate gradient iteration.
+struct Direction<T> {
+    step: Vec<T>,
+    /// Whether negative curvature was met.
+    negative_curvature: bool,
+}
+
+/// Everything about the iteration that is not the current point.
+struct State<T> {
+    globalization: Globalization<T>,
+    /// The current trust-region radius.
+    radius: T,
+    max_radius: T,
+    modifications: usize,
+}
+
+impl<T: Real> State<T> {
+    /// Approximately solves `H·p = -gradient` by conjugate gradient, where `product(v)` is `H·v`.
+    ///
+    /// The iteration stops once the residual is below `min(½, √‖g‖)·‖g‖`, on negative curvature,
+    /// or, if `radius` is given, when leaving the ball of that radius.
+    fn direction<P>(&self, product: &P, gradient: &[T], radius: Option<T>) -> Direction<T>
+    where
+        P: Fn(&[T]) -> Vec<T>,
+    {
+        let n = gradient.len();
+        let gradient_norm = norm(gradient);
+        let half = T::from_f64(0.5);
+        let root = gradient_norm.sqrt();
+        let tolerance = gradient_norm * if root < half { root } else { half };
+
+        let mut z = vec![T::zero(); n];
+        let mut r = gradient.to_vec();
+        let mut d: Vec<T> = gradient.iter().map(|&g| -g).collect();
+        let mut rr = dot(&r, &r);
+
+        for j in 0..n.max(1) {
+            let hd = product(&d);
+            let curvature = dot(&d, &hd);
+
+            if curvature <= T::zero() {
+                let step = match radius {
+                    Some(radius) => to_boundary(&z, &d, radius),
+                    None if j == 0 => d,
+                    None => z,
+                };
+                return Direction {
+                    step,
+                    negative_curvature: true,
+                };
+            }
+
+            let alpha = rr / curvature;
+            let next: Vec<T> = z.iter().zip(&d).map(|(&z, &d)| z + alpha * d).collect();
+            if let Some(radius) = radius {
+                if norm(&next) >= radius {
+                    return Direction {
+                        step: to_boundary(&z, &d, radius),
+                        negative_curvature: false,
+                    };
+                }
+            }
+            z = next;
+            for (r, &hd) in r.iter_mut().zip(&hd) {
+                *r = *r + alpha * hd;
+            }
+
+            let next_rr = dot(&r, &r);
+            if next_rr.sqrt() <= tolerance {
+                break;
+            }
+            let beta = next_rr / rr;
+            rr = next_rr;
+            for (d, &r) in d.iter_mut().zip(&r) {
+                *d = beta * *d - r;
+            }
+        }
+
+        Direction {
+            step: z,
+            negative_curvature: false,
+        }
+    }
+
+    /// Computes the next iterate from `x`, where `value` and `gradient` are the value and gradient
+    /// of the function at `x`, and `product(v)` is the product of the Hessian at `x` with `v`.
+    ///
+    /// `x` is returned when it is a minimiser to working precision, see [`failed_search`]. When
+    /// the trust region shrinks to the rounding errors of `x`, this is judged along the
+    /// unrestricted direction.
+    fn advance<O, P>(
+        &mut self,
+        objective: &O,
+        product: &P,
+        x: &[T],
+        value: T,
+        gradient: &[T],
+    ) -> Advance<T>
+    where
+        O: Fn(&[T]) -> (T, Vec<T>),
+        P: Fn(&[T]) -> Vec<T>,
+    {
+        match self.globalization {
+            Globalization::LineSearch(line_search) => {
+                let direction = self.direction(product, gradient, None);
+                if direction.negative_curvature {
+                    self.modifications += 1;
+                }
+                if direction.step.iter().any(|d| !d.is_finite()) {
+                    return Err(NewtonError::NonFiniteDerivative { at: x.to_vec() });
+                }
+
+                match line_search.search(objective, x, value, gradient, &direction.step, T::one()) {
+                    Some(trial) => Ok((trial.point, trial.value, trial.gradient)),
+                    None => failed_search(x, value, gradient, &direction.step),
+                }
+            }
+            Globalization::TrustRegion(_) => loop {
+                let radius = self.radius;
+                let direction = self.direction(product, gradient, Some(radius));
+                if direction.negative_curvature {
+                    self.modifications += 1;
+                }
+                let step = direction.step;
+                if step.iter().any(|d| !d.is_finite()) {
+                    return Err(NewtonError::NonFiniteDerivative { at: x.to_vec() });
+                }
+
+                let next: Vec<T> = x.iter().zip(&step).map(|(&x, &s)| x + s).collect();
+                let (next_value, next_gradient) = objective(&next);
+
+                let half = T::from_f64(0.5);
+                let predicted = -(dot(gradient, &step) + half * dot(&step, &product(&step)));
+                let actual = value - next_value;
+                let ratio = if predicted > T::zero() && actual.is_finite() {
+                    actual / predicted
+                } else {
+                    T::zero()
+                };
+
+                let step_norm = norm(&step);
+                if ratio < T::from_f64(0.25) {
+                    self.radius = T::from_f64(0.25) * step_norm;
+                } else if ratio > T::from_f64(0.75) && step_norm >= T::from_f64(0.99) * radius {
+                    let doubled = radius + radius;
+                    self.radius = if doubled > self.max_radius {
+                        self.max_radius
+                    } else {
+                        doubled
+                    };
+                }
+
+                if ratio > T::from_f64(1E-4) {
+                    return Ok((next, next_value, next_gradient));
+                }
+                if self.radius <= T::epsilon() * (T::one() + norm(x)) {
+                    let direction = self.direction(product, gradient, None);
+                    return failed_search(x, value, gradient, &direction.step);
+                }
+            },
+        }
+    }
+}
+
+/// The point `z + τ·d`, with `τ >= 0`, on the sphere of radius `radius`, where `‖z‖ <= radius`.
+fn to_boundary<T: Real>(z: &[T], d: &[T], radius: T) -> Vec<T> {
+    let a = dot(d, d);
+    let b = dot(z, d);
+    let c = dot(z, z) - radius * radius;
+    let tau = (-b + (b * b - a * c).sqrt()) / a;
+    z.iter().zip(d).map(|(&z, &d)| z + tau * d).collect()
+}
+
+/// An iterator that returns successive iterations of the truncated Newton method, also known as
+/// Newton–CG, for unconstrained minimisation.
+///
+/// The Hessian is never formed: each Newton system is solved inexactly by conjugate gradient,
+/// which only needs products of the Hessian with vectors. These products are either given, or
+/// approximated by finite differences of the gradient. The conjugate gradient iteration stops
+/// early on negative curvature, which is counted in [`Optimum::modifications`], and the steps are
+/// globalised according to [`Globalization`].
+///
+/// The iterator stops when a direction is not finite, or no step decreases the function.
+///
+/// # Example
+///
+/// ```
+/// use generic_newton::NewtonCg;
+///
+/// // The Rosenbrock function, whose minimum is at (1, 1).
+/// let mut n = NewtonCg::new(
+///     vec![-1.2, 1.], // Initial guess
+///     |x: &[f64]| (1. - x[0]).powi(2) + 100. * (x[1] - x[0] * x[0]).powi(2), // The function
+///     |x: &[f64]| vec![
+///         -2. * (1. - x[0]) - 400. * x[0] * (x[1] - x[0] * x[0]),
+///         200. * (x[1] - x[0] * x[0]),
+///     ], // Its gradient
+///     |x: &[f64], v: &[f64]| vec![
+///         (2. - 400. * (x[1] - 3. * x[0] * x[0])) * v[0] - 400. * x[0] * v[1],
+///         -400. * x[0] * v[0] + 200. * v[1],
+///     ], // The product of its Hessian with a vector
+/// );
+///
+/// let minimiser = n.nth(100).unwrap();
+/// assert!((minimiser[0] - 1.).abs() < 1E-10 && (minimiser[1] - 1.).abs() < 1E-10);
+/// ```
+pub struct NewtonCg<T, F, G, H = HessianProduct<T>>
+where
+    T: Real,
+    F: Fn(&[T]) -> T,
+    G: Fn(&[T]) -> Vec<T>,
+    H: Fn(&[T], &[T]) -> Vec<T>,
+{
+    current: Vec<T>,
+    current_value: Option<(T, Vec<T>)>,
+    func: F,
+    gradient: G,
+    /// The Hessian-vector product, approximated by finite differences if absent.
+    hessian_product: Option<H>,
+    state: State<T>,
+}
+
+impl<T, F, G, H> NewtonCg<T, F, G, H>
+where
+    T: Real,
+    F: Fn(&[T]) -> T,
+    G: Fn(&[T]) -> Vec<T>,
+    H: Fn(&[T], &[T]) -> Vec<T>,
+{
+    /// Creates a new `NewtonCg` iterator, using the default [`Wolfe`] line search.
+    ///
+    /// - `func` is the actual function to minimise
+    /// - `gradient` is it's gradient
+    /// - `hessian_product(x, v)` is the product of it's Hessian matrix at `x` with `v`.
+    pub fn new(initial_guess: Vec<T>, func: F, gradient: G, hessian_product: H) -> Self {
+        NewtonCg::with_product(initial_guess, func, gradient, Some(hessian_product))
+    }
+
+    /// Creates a new `NewtonCg` iterator, approximating the Hessian-vector products if
+    /// `hessian_product` is `None`.
+    fn with_product(
+        initial_guess: Vec<T>,
+        func: F,
+        gradient: G,
+        hessian_product: Option<H>,
+    ) -> Self {
+        NewtonCg {
+            current: initial_guess,
+            current_value: None,
+            func,
+            gradient,
+            hessian_product,
+            state: State {
+                globalization: Globalization::LineSearch(Wolfe::default()),
+                radius: T::one(),
+                max_radius: T::from_f64(1E10),
+                modifications: 0,
+            },
+        }
+    }
+
+    /// Sets how steps are computed from the directions.
+    pub fn with_globalization(mut self, globalization: Globalization<T>) -> Self {
+        if let Globalization::TrustRegion(radius) = globalization {
+            self.state.radius = radius;
+        }
+        self.state.globalization = globalization;
+        self
+    }
+
+    /// Sets the largest trust-region radius.
+    pub fn with_max_radius(mut self, max_radius: T) -> Self {
+        self.state.max_radius = max_radius;
+        self
+    }
+
+    /// Iterates until `criteria` are met, and returns the minimiser found.
+    ///
+    /// The residual tolerance of `criteria` applies to the norm of the gradient. The iteration
+    /// also stops at a minimiser to working precision, where no step can measurably decrease the
+    /// function. Otherwise, a line search or a trust region that cannot decrease the function
+    /// fails with [`NewtonError::LineSearchFailed`].
+    pub fn solve(self, criteria: &Criteria<T>) -> Result<Optimum<T>, NewtonError<Vec<T>>> {
+        let (func, gradient) = (&self.func, &self.gradient);
+        let objective = |x: &[T]| (func(x), gradient(x));
+        let hessian_product = self.hessian_product.as_ref();
+        let mut state = self.state;
+        let cached: Cached<T> = Cell::new(self.current_value);
+        let value = Cell::new(None);
+
+        let solution = solve_system_with(
+            criteria,
+            self.current,
+            |x| {
+                let (fx, gx) = cached.take().unwrap_or_else(|| objective(x));
+                value.set(Some(fx));
+                gx
+            },
+            |x, gx| {
+                let fx = value.get().expect("the residual was computed");
+                let product = |v: &[T]| match hessian_product {
+                    Some(hessian_product) => hessian_product(x, v),
+                    None => finite_difference_product(gradient, x, gx, v),
+                };
+                let (next, next_value, next_gradient) =
+                    state.advance(&objective, &product, x, fx, gx)?;
+                cached.set(Some((next_value, next_gradient)));
+                Ok(next)
+            },
+        )?;
+
+        let fx = value.get().expect("the residual was computed");
+        Ok(Optimum::new(solution, fx, state.modifications))
+    }
+}
+
+impl<T, F, G> NewtonCg<T, F, G>
+where
+    T: Real,
+    F: Fn(&[T]) -> T,
+    G: Fn(&[T]) -> Vec<T>,
+{
+    /// Creates a new `NewtonCg` iterator, whose Hessian-vector products are approximated by
+    /// forward differences of the gradient.
+    ///
+    /// - `func` is the actual function to minimise
+    /// - `gradient` is it's gradient.
+    pub fn with_finite_differences(initial_guess: Vec<T>, func: F, gradient: G) -> Self {
+        NewtonCg::with_product(initial_guess, func, gradient, None)
+    }
+}
+
+/// Approximates the product of the Hessian at `x` with `v` by `(∇f(x + h·v) - ∇f(x)) / h`, where
+/// `value` is `∇f(x)`, and `h` is scaled to the size of `x` and `v`.
+fn finite_difference_product<T, G>(gradient: &G, x: &[T], value: &[T], v: &[T]) -> Vec<T>
+where
+    T: Real,
+    G: Fn(&[T]) -> Vec<T>,
+{
+    let v_norm = norm(v);
+    if v_norm == T::zero() {
+        return vec![T::zero(); v.len()];
+    }
+    let h = T::epsilon().sqrt() * (T::one() + norm(x)) / v_norm;
+    let shifted: Vec<T> = x.iter().zip(v).map(|(&x, &v)| x + h * v).collect();
+    gradient(&shifted)
+        .iter()
+        .zip(value)
+        .map(|(&s, &g)| (s - g) / h)
+        .collect()
+}
+
+impl<T, F, G, H> Iterator for NewtonCg<T, F, G, H>
+where
+    T: Real,
+    F: Fn(&[T]) -> T,
+    G: Fn(&[T]) -> Vec<T>,
+    H: Fn(&[T], &[T]) -> Vec<T>,
+{
+    type Item = Vec<T>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let (func, gradient) = (&self.func, &self.gradient);
+        let objective = |x: &[T]| (func(x), gradient(x));
+
+        let (value, grad) = match self.current_value.take() {
+            Some(cached) => cached,
+            None => objective(&self.current),
+        };
+        let x = &self.current;
+        let hessian_product = self.hessian_product.as_ref();
+        let product = |v: &[T]| match hessian_product {
+            Some(hessian_product) => hessian_product(x, v),
+            None => finite_difference_product(gradient, x, &grad, v),
+        };
+        let (next, next_value, next_gradient) = self
+            .state
+            .advance(&objective, &product, x, value, &grad)
+            .ok()?;
+
+        self.current = next;
+        self.current_value = Some((next_value, next_gradient));
+        Some(self.current.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{Globalization, NewtonCg};
+    use crate::{Criteria, NewtonError};
+
+    /// A smooth function of many variables, with a saddle point at the origin.
+    fn func(x: &[f64]) -> f64 {
+        x.iter()
+            .enumerate()
+            .map(|(i, x)| (x * x - 1.).powi(2) + 0.1 * (i + 1) as f64 * x * x)
+            .sum()
+    }
+
+    fn gradient(x: &[f64]) -> Vec<f64> {
+        x.iter()
+            .enumerate()
+            .map(|(i, x)| 4. * x * (x * x - 1.) + 0.2 * (i + 1) as f64 * x)
+            .collect()
+    }
+
+    fn product(x: &[f64], v: &[f64]) -> Vec<f64> {
+        x.iter()
+            .zip(v)
+            .enumerate()
+            .map(|(i, (x, v))| (12. * x * x - 4. + 0.2 * (i + 1) as f64) * v)
+            .collect()
+    }
+
+    #[test]
+    fn globalizations_agree() {
+        let n = 15;
+        let start: Vec<f64> = (0..n).map(|i| 0.05 + 0.01 * i as f64).collect();
+        let criteria = Criteria {
+            residual_tolerance: 1E-8,
+            ..Criteria::default()
+        };
+
+        let line_search = NewtonCg::new(start.clone(), func, gradient, product)
+            .solve(&criteria)
+            .unwrap();
+        let trust_region = NewtonCg::new(start.clone(), func, gradient, product)
+            .with_globalization(Globalization::TrustRegion(1.))
+            .solve(&criteria)
+            .unwrap();
+        let finite_differences = NewtonCg::with_finite_differences(start, func, gradient)
+            .solve(&criteria)
+            .unwrap();
+
+        for optimum in &[&line_search, &trust_region, &finite_differences] {
+            assert!(optimum.gradient_norm <= 1E-8);
+            // The start is close to the saddle, where the curvature is negative.
+            assert!(optimum.modifications > 0);
+        }
+        // Each coordinate ends at a minimum of its own term, not at the saddle.
+        for optimum in &[line_search, trust_region, finite_differences] {
+            for (i, x) in optimum.minimiser.iter().enumerate() {
+                let expected = (1. - 0.05 * (i + 1) as f64).sqrt();
+                assert!((x.abs() - expected).abs() < 1E-8);
+            }
+        }
+    }
+
+    #[test]
+    fn trust_region_bounds_steps() {
+        let mut n = NewtonCg::new(vec![0.1; 10], func, gradient, product)
+            .with_globalization(Globalization::TrustRegion(0.05));
+        let mut previous = n.current.clone();
+        for _ in 0..10 {
+            let radius = n.state.radius;
+            let x = n.next().unwrap();
+            let step: f64 = x.iter().zip(&previous).map(|(a, b)| (a - b).powi(2)).sum();
+            assert!(step.sqrt() <= radius * (1. + 1E-12));
+            assert!(func(&x) < func(&previous));
+            previous = x;
+        }
+    }
+
+    #[test]
+    fn reports_failed_steps() {
+        // A gradient of the wrong sign makes every direction an ascent one.
+        for &globalization in &[
+            Globalization::LineSearch(Default::default()),
+            Globalization::TrustRegion(1.),
+        ] {
+            let result = NewtonCg::new(
+                vec![1.],
+                |x: &[f64]| x[0] * x[0],
+                |x: &[f64]| vec![-2. * x[0]],
+                |_: &[f64], v: &[f64]| vec![2. * v[0]],
+            )
+            .with_globalization(globalization)
+            .solve(&Criteria::default());
+            assert_eq!(result, Err(NewtonError::LineSearchFailed { at: vec![1.] }));
+        }
+    }
+}
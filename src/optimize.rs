@@ -0,0 +1,330 @@
+use std::cell::Cell;
+use std::iter::Iterator;
+
+use crate::linalg::{dot, norm, Cholesky, Matrix};
+use crate::solve::solve_system_with;
+use crate::{Criteria, NewtonError, Real, Solution, Wolfe};
+
+/// The result of an unconstrained minimisation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Optimum<T> {
+    /// The minimiser found.
+    pub minimiser: Vec<T>,
+    /// The value of the function at `minimiser`.
+    pub value: T,
+    /// The gradient at `minimiser`.
+    pub gradient: Vec<T>,
+    /// The norm of `gradient`.
+    pub gradient_norm: T,
+    /// The number of iterations performed.
+    pub iterations: usize,
+    /// The number of times the Hessian, or its approximation, had to be modified to be positive
+    /// definite.
+    pub modifications: usize,
+}
+
+impl<T: Real> Optimum<T> {
+    /// Builds an `Optimum` from the solution of `∇f(x) = 0`.
+    pub(crate) fn new(solution: Solution<Vec<T>>, value: T, modifications: usize) -> Self {
+        Optimum {
+            gradient_norm: norm(&solution.residual),
+            minimiser: solution.root,
+            value,
+            gradient: solution.residual,
+            iterations: solution.iterations,
+            modifications,
+        }
+    }
+}
+
+/// The value and gradient of the function at the next iterate, cached to avoid computing them
+/// twice.
+pub(crate) type Cached<T> = Cell<Option<(T, Vec<T>)>>;
+
+/// The next iterate, along with the value and gradient of the function there.
+pub(crate) type Advance<T> = Result<(Vec<T>, T, Vec<T>), NewtonError<Vec<T>>>;
+
+/// The result of a failed line search from `x` along the descent direction `direction`.
+///
+/// When the first-order decrease is below the rounding errors of `value`, or the whole step below
+/// those of `x`, no step can measurably decrease the function: `x` is a minimiser to working
+/// precision, and returned unchanged so that the iteration converges. Otherwise the search
+/// genuinely failed, which is reported as [`NewtonError::LineSearchFailed`].
+pub(crate) fn failed_search<T: Real>(
+    x: &[T],
+    value: T,
+    gradient: &[T],
+    direction: &[T],
+) -> Advance<T> {
+    let rounding = T::from_f64(16.) * T::epsilon();
+    if dot(gradient, direction).abs() <= rounding * value.abs()
+        || norm(direction) <= rounding * norm(x)
+    {
+        Ok((x.to_vec(), value, gradient.to_vec()))
+    } else {
+        Err(NewtonError::LineSearchFailed { at: x.to_vec() })
+    }
+}
+
+/// The result of a backtracking search from `point` that accepted no fraction of `step`.
+///
+/// When `step` is below the rounding errors of `point`, no fraction of it can make progress:
+/// `point` is returned unchanged so that the iteration converges. Otherwise the search genuinely
+/// failed, which is reported as [`NewtonError::LineSearchFailed`].
+pub(crate) fn stalled_search<T: Real>(
+    point: &[T],
+    step: &[T],
+) -> Result<Vec<T>, NewtonError<Vec<T>>> {
+    if norm(step) <= T::from_f64(16.) * T::epsilon() * norm(point) {
+        Ok(point.to_vec())
+    } else {
+        Err(NewtonError::LineSearchFailed { at: point.to_vec() })
+    }
+}
+
+/// Everything about the iteration that is not the current point.
+struct State<T> {
+    line_search: Wolfe<T>,
+    modifications: usize,
+}
+
+impl<T: Real> State<T> {
+    /// Computes the next iterate from `x`, where `value` and `gradient` are the value and gradient
+    /// of the function at `x`, returning it along with its value and gradient.
+    ///
+    /// `x` is returned when it is a minimiser to working precision, see [`failed_search`].
+    fn advance<O, H>(
+        &mut self,
+        objective: &O,
+        hessian: &H,
+        x: &[T],
+        value: T,
+        gradient: &[T],
+    ) -> Advance<T>
+    where
+        O: Fn(&[T]) -> (T, Vec<T>),
+        H: Fn(&[T]) -> Matrix<T>,
+    {
+        let (cholesky, shift) = Cholesky::modified(&hessian(x))
+            .ok_or_else(|| NewtonError::NonFiniteDerivative { at: x.to_vec() })?;
+        if shift > T::zero() {
+            self.modifications += 1;
+        }
+
+        let direction: Vec<T> = cholesky.solve(gradient).into_iter().map(|d| -d).collect();
+        match self
+            .line_search
+            .search(objective, x, value, gradient, &direction, T::one())
+        {
+            Some(trial) => Ok((trial.point, trial.value, trial.gradient)),
+            None => failed_search(x, value, gradient, &direction),
+        }
+    }
+}
+
+/// An iterator that returns successive iterations of Newton's method for unconstrained
+/// minimisation.
+///
+/// Each iteration solves `H(x)·d = -∇f(x)`, where `H` is the Hessian matrix of `f`, and searches
+/// along `d` for a step satisfying the strong [`Wolfe`] conditions, trying the full Newton step
+/// first. When the Hessian is not positive definite, a multiple of the identity is added to it
+/// by [`Cholesky::modified`], so that `d` is always a descent direction.
+///
+/// The iterator stops when the Hessian is not finite, or the line search fails.
+///
+/// # Example
+///
+/// ```
+/// use generic_newton::{Matrix, NewtonOptimizer};
+///
+/// // The Rosenbrock function, whose minimum is at (1, 1).
+/// let mut n = NewtonOptimizer::new(
+///     vec![-1.2, 1.], // Initial guess
+///     |x: &[f64]| (1. - x[0]).powi(2) + 100. * (x[1] - x[0] * x[0]).powi(2), // The function
+///     |x: &[f64]| vec![
+///         -2. * (1. - x[0]) - 400. * x[0] * (x[1] - x[0] * x[0]),
+///         200. * (x[1] - x[0] * x[0]),
+///     ], // Its gradient
+///     |x: &[f64]| Matrix::from_rows(&[
+///         [2. - 400. * (x[1] - 3. * x[0] * x[0]), -400. * x[0]],
+///         [-400. * x[0], 200.],
+///     ]), // Its Hessian
+/// );
+///
+/// let minimiser = n.nth(50).unwrap();
+/// assert!((minimiser[0] - 1.).abs() < 1E-12 && (minimiser[1] - 1.).abs() < 1E-12);
+/// ```
+pub struct NewtonOptimizer<T, F, G, H>
+where
+    T: Real,
+    F: Fn(&[T]) -> T,
+    G: Fn(&[T]) -> Vec<T>,
+    H: Fn(&[T]) -> Matrix<T>,
+{
+    current: Vec<T>,
+    current_value: Option<(T, Vec<T>)>,
+    func: F,
+    gradient: G,
+    hessian: H,
+    state: State<T>,
+}
+
+impl<T, F, G, H> NewtonOptimizer<T, F, G, H>
+where
+    T: Real,
+    F: Fn(&[T]) -> T,
+    G: Fn(&[T]) -> Vec<T>,
+    H: Fn(&[T]) -> Matrix<T>,
+{
+    /// Creates a new `NewtonOptimizer` iterator, using the default [`Wolfe`] line search.
+    ///
+    /// - `func` is the actual function to minimise
+    /// - `gradient` is it's gradient
+    /// - `hessian` is it's Hessian matrix.
+    pub fn new(initial_guess: Vec<T>, func: F, gradient: G, hessian: H) -> Self {
+        NewtonOptimizer {
+            current: initial_guess,
+            current_value: None,
+            func,
+            gradient,
+            hessian,
+            state: State {
+                line_search: Wolfe::default(),
+                modifications: 0,
+            },
+        }
+    }
+
+    /// Sets the parameters of the line search.
+    pub fn with_line_search(mut self, line_search: Wolfe<T>) -> Self {
+        self.state.line_search = line_search;
+        self
+    }
+
+    /// Iterates until `criteria` are met, and returns the minimiser found.
+    ///
+    /// The residual tolerance of `criteria` applies to the norm of the gradient. The iteration
+    /// also stops at a minimiser to working precision, where no step can measurably decrease the
+    /// function. Otherwise, a line search that cannot decrease the function fails with
+    /// [`NewtonError::LineSearchFailed`].
+    pub fn solve(self, criteria: &Criteria<T>) -> Result<Optimum<T>, NewtonError<Vec<T>>> {
+        let (func, gradient) = (&self.func, &self.gradient);
+        let objective = |x: &[T]| (func(x), gradient(x));
+        let hessian = &self.hessian;
+        let mut state = self.state;
+        let cached: Cached<T> = Cell::new(self.current_value);
+        let value = Cell::new(None);
+
+        let solution = solve_system_with(
+            criteria,
+            self.current,
+            |x| {
+                let (fx, gx) = cached.take().unwrap_or_else(|| objective(x));
+                value.set(Some(fx));
+                gx
+            },
+            |x, gx| {
+                let fx = value.get().expect("the residual was computed");
+                let (next, next_value, next_gradient) =
+                    state.advance(&objective, hessian, x, fx, gx)?;
+                cached.set(Some((next_value, next_gradient)));
+                Ok(next)
+            },
+        )?;
+
+        let fx = value.get().expect("the residual was computed");
+        Ok(Optimum::new(solution, fx, state.modifications))
+    }
+}
+
+impl<T, F, G, H> Iterator for NewtonOptimizer<T, F, G, H>
+where
+    T: Real,
+    F: Fn(&[T]) -> T,
+    G: Fn(&[T]) -> Vec<T>,
+    H: Fn(&[T]) -> Matrix<T>,
+{
+    type Item = Vec<T>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let (func, gradient) = (&self.func, &self.gradient);
+        let objective = |x: &[T]| (func(x), gradient(x));
+
+        let (value, grad) = match self.current_value.take() {
+            Some(cached) => cached,
+            None => objective(&self.current),
+        };
+        let (next, next_value, next_gradient) = self
+            .state
+            .advance(&objective, &self.hessian, &self.current, value, &grad)
+            .ok()?;
+
+        self.current = next;
+        self.current_value = Some((next_value, next_gradient));
+        Some(self.current.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::NewtonOptimizer;
+    use crate::{Criteria, Matrix, NewtonError};
+
+    /// A function with a saddle point at the origin and minima at (±1, 0).
+    fn func(x: &[f64]) -> f64 {
+        (x[0] * x[0] - 1.).powi(2) + x[1] * x[1]
+    }
+
+    fn gradient(x: &[f64]) -> Vec<f64> {
+        vec![4. * x[0] * (x[0] * x[0] - 1.), 2. * x[1]]
+    }
+
+    fn hessian(x: &[f64]) -> Matrix<f64> {
+        Matrix::from_rows(&[[12. * x[0] * x[0] - 4., 0.], [0., 2.]])
+    }
+
+    #[test]
+    fn modifies_indefinite_hessian() {
+        let criteria = Criteria {
+            residual_tolerance: 1E-12,
+            ..Criteria::default()
+        };
+        let optimum = NewtonOptimizer::new(vec![0.1, 1.], func, gradient, hessian)
+            .solve(&criteria)
+            .unwrap();
+
+        assert!((optimum.minimiser[0] - 1.).abs() < 1E-12);
+        assert!(optimum.minimiser[1].abs() < 1E-12);
+        assert!(optimum.gradient_norm <= 1E-12);
+        assert!(optimum.value < 1E-20);
+        assert!(optimum.modifications >= 1);
+
+        // Far from the saddle, the Hessian is positive definite all the way.
+        let optimum = NewtonOptimizer::new(vec![2., 1.], func, gradient, hessian)
+            .solve(&criteria)
+            .unwrap();
+        assert_eq!(optimum.modifications, 0);
+    }
+
+    #[test]
+    fn decreases() {
+        let mut previous = func(&[0.1, 1.]);
+        for x in NewtonOptimizer::new(vec![0.1, 1.], func, gradient, hessian).take(20) {
+            assert!(func(&x) <= previous);
+            previous = func(&x);
+        }
+    }
+
+    #[test]
+    fn reports_failed_line_search() {
+        // A gradient of the wrong sign makes every direction an ascent one.
+        let result = NewtonOptimizer::new(
+            vec![1.],
+            |x: &[f64]| x[0] * x[0],
+            |x: &[f64]| vec![-2. * x[0]],
+            |_: &[f64]| Matrix::from_rows(&[[2.]]),
+        )
+        .solve(&Criteria::default());
+        assert_eq!(result, Err(NewtonError::LineSearchFailed { at: vec![1.] }));
+    }
+}
@@ -0,0 +1,227 @@
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+use crate::{Newton, Real};
+
+/// A polynomial with real coefficients, stored in increasing order of degree.
+///
+/// Polynomials can be evaluated at any type their coefficients convert into, which includes
+/// their own type and [`Complex`](crate::Complex) numbers.
+///
+/// # Example
+///
+/// ```
+/// use generic_newton::Polynomial;
+///
+/// // x³ - 2x - 5
+/// let p = Polynomial::new(vec![-5f64, -2., 0., 1.]);
+///
+/// assert_eq!(p.evaluate(2.), (-1., 10.));
+///
+/// let root = p.newton(2f64).nth(10).unwrap();
+/// assert!((root - 2.0945514815423265).abs() < 1E-15);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Polynomial<T> {
+    /// The coefficients, with no trailing zero.
+    coefficients: Vec<T>,
+}
+
+impl<T: Real> Polynomial<T> {
+    /// Creates a polynomial from its coefficients, constant term first.
+    ///
+    /// Trailing zero coefficients are dropped.
+    pub fn new(mut coefficients: Vec<T>) -> Self {
+        while coefficients.last() == Some(&T::zero()) {
+            coefficients.pop();
+        }
+        Polynomial { coefficients }
+    }
+
+    /// Creates the monic polynomial whose roots are `roots`.
+    pub fn from_roots(roots: &[T]) -> Self {
+        roots
+            .iter()
+            .fold(Polynomial::new(vec![T::one()]), |p, &root| {
+                p * Polynomial::new(vec![-root, T::one()])
+            })
+    }
+
+    /// The coefficients, constant term first, with no trailing zero.
+    pub fn coefficients(&self) -> &[T] {
+        &self.coefficients
+    }
+
+    /// The degree, or `None` for the zero polynomial.
+    pub fn degree(&self) -> Option<usize> {
+        self.coefficients.len().checked_sub(1)
+    }
+
+    /// The value at `x`, by Horner's scheme.
+    pub fn value<U>(&self, x: U) -> U
+    where
+        U: From<T> + Add<Output = U> + Mul<Output = U> + Copy,
+    {
+        self.coefficients
+            .iter()
+            .rev()
+            .fold(U::from(T::zero()), |value, &c| value * x + U::from(c))
+    }
+
+    /// The value and the derivative at `x`, computed together by Horner's scheme.
+    pub fn evaluate<U>(&self, x: U) -> (U, U)
+    where
+        U: From<T> + Add<Output = U> + Mul<Output = U> + Copy,
+    {
+        let zero = U::from(T::zero());
+        self.coefficients
+            .iter()
+            .rev()
+            .fold((zero, zero), |(value, derivative), &c| {
+                (value * x + U::from(c), derivative * x + value)
+            })
+    }
+
+    /// The derivative.
+    pub fn derivative(&self) -> Self {
+        Polynomial::new(
+            self.coefficients
+                .iter()
+                .enumerate()
+                .skip(1)
+                .map(|(power, &c)| c * T::from_f64(power as f64))
+                .collect(),
+        )
+    }
+
+    /// Creates a [`Newton`] iterator on this polynomial, starting from `initial_guess`.
+    ///
+    /// The starting point may be of any type the coefficients convert into, such as a
+    /// [`Complex`](crate::Complex) number to reach complex roots.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use generic_newton::{Complex, Polynomial};
+    ///
+    /// // x² + 1
+    /// let p = Polynomial::new(vec![1f64, 0., 1.]);
+    ///
+    /// let root = p.newton(Complex::new(0.5, 0.5)).nth(20).unwrap();
+    /// assert!((root - Complex::i()).norm() < 1E-15);
+    /// ```
+    #[allow(clippy::type_complexity)]
+    pub fn newton<U>(&self, initial_guess: U) -> Newton<U, impl Fn(U) -> U, impl Fn(U) -> U>
+    where
+        U: From<T> + Add<Output = U> + Mul<Output = U> + Div<Output = U> + Sub<Output = U> + Copy,
+    {
+        let func = self.clone();
+        let derivative = self.derivative();
+        Newton::new(
+            initial_guess,
+            move |x| func.value(x),
+            move |x| derivative.value(x),
+        )
+    }
+}
+
+impl<T: Real> Add for Polynomial<T> {
+    type Output = Self;
+    fn add(self, other: Self) -> Self {
+        let (mut long, short) = if self.coefficients.len() < other.coefficients.len() {
+            (other.coefficients, self.coefficients)
+        } else {
+            (self.coefficients, other.coefficients)
+        };
+        for (c, &d) in long.iter_mut().zip(&short) {
+            *c = *c + d;
+        }
+        Polynomial::new(long)
+    }
+}
+
+impl<T: Real> Sub for Polynomial<T> {
+    type Output = Self;
+    fn sub(self, other: Self) -> Self {
+        self + -other
+    }
+}
+
+impl<T: Real> Mul for Polynomial<T> {
+    type Output = Self;
+    fn mul(self, other: Self) -> Self {
+        if self.coefficients.is_empty() || other.coefficients.is_empty() {
+            return Polynomial::new(Vec::new());
+        }
+        let mut product = vec![T::zero(); self.coefficients.len() + other.coefficients.len() - 1];
+        for (i, &c) in self.coefficients.iter().enumerate() {
+            for (j, &d) in other.coefficients.iter().enumerate() {
+                product[i + j] = product[i + j] + c * d;
+            }
+        }
+        Polynomial::new(product)
+    }
+}
+
+impl<T: Real> Mul<T> for Polynomial<T> {
+    type Output = Self;
+    fn mul(self, other: T) -> Self {
+        Polynomial::new(self.coefficients.into_iter().map(|c| c * other).collect())
+    }
+}
+
+impl<T: Real> Neg for Polynomial<T> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Polynomial::new(self.coefficients.into_iter().map(|c| -c).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::Polynomial;
+    use crate::{Complex, Criteria};
+
+    #[test]
+    fn arithmetic() {
+        let p = Polynomial::new(vec![1f64, 2., 3.]);
+        let q = Polynomial::new(vec![-1f64, 0., -3., 0.]);
+
+        assert_eq!(q.degree(), Some(2));
+        assert_eq!((p.clone() + q.clone()).coefficients(), &[0., 2.]);
+        assert_eq!((p.clone() - p.clone()).degree(), None);
+        assert_eq!(
+            (p.clone() * q.clone()).coefficients(),
+            &[-1., -2., -6., -6., -9.]
+        );
+        assert_eq!((p.clone() * 2.).coefficients(), &[2., 4., 6.]);
+        assert_eq!(p.derivative().coefficients(), &[2., 6.]);
+        assert_eq!(
+            Polynomial::from_roots(&[1f64, -2.]).coefficients(),
+            &[-2., 1., 1.]
+        );
+
+        for &x in &[-1.5f64, 0., 0.25, 3.] {
+            let (value, derivative) = (p.clone() * q.clone()).evaluate(x);
+            assert_eq!(value, p.value(x) * q.value(x));
+            assert_eq!(
+                derivative,
+                p.derivative().value(x) * q.value(x) + p.value(x) * q.derivative().value(x)
+            );
+        }
+    }
+
+    #[test]
+    fn newton_finds_roots() {
+        let p = Polynomial::from_roots(&[-1f64, 0.5, 2.]);
+        for &(start, root) in &[(-3f64, -1f64), (0.4, 0.5), (5., 2.)] {
+            let solution = p.newton(start).solve(&Criteria::default()).unwrap();
+            assert!((solution.root - root).abs() < 1E-14);
+        }
+
+        // z² - 2z + 5, whose roots are 1 ± 2i
+        let p = Polynomial::new(vec![5f64, -2., 1.]);
+        let root = p.newton(Complex::new(0f64, -1.)).nth(30).unwrap();
+        assert!((root - Complex::new(1., -2.)).norm() < 1E-14);
+    }
+}
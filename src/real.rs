@@ -0,0 +1,126 @@
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+/// Real numbers usable with the tolerance-based parts of the crate.
+///
+/// The plain iterators only need the arithmetic operators, but deciding when to stop requires
+/// comparisons, absolute values and a notion of finiteness. This trait gathers those along with
+/// the common elementary functions, and is implemented for `f32` and `f64`.
+pub trait Real:
+    Copy
+    + PartialOrd
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+{
+    /// The additive identity.
+    fn zero() -> Self;
+
+    /// The multiplicative identity.
+    fn one() -> Self;
+
+    /// The difference between `1` and the next representable value.
+    fn epsilon() -> Self;
+
+    /// Converts a `f64` constant, possibly losing precision.
+    fn from_f64(value: f64) -> Self;
+
+    /// The absolute value.
+    fn abs(self) -> Self;
+
+    /// Whether this value is neither infinite nor NaN.
+    fn is_finite(self) -> bool;
+
+    /// Whether this value is NaN.
+    fn is_nan(self) -> bool;
+
+    /// The square root.
+    fn sqrt(self) -> Self;
+
+    /// Raises to an integer power.
+    fn powi(self, n: i32) -> Self;
+
+    /// Raises to a real power.
+    ///
+    /// Used where exponents are not integers, such as the Eisenstat–Walker forcing terms of
+    /// [`NewtonKrylov`](crate::NewtonKrylov).
+    fn powf(self, n: Self) -> Self;
+
+    /// The exponential.
+    fn exp(self) -> Self;
+
+    /// The natural logarithm.
+    fn ln(self) -> Self;
+
+    /// The sine, in radians.
+    fn sin(self) -> Self;
+
+    /// The cosine, in radians.
+    fn cos(self) -> Self;
+}
+
+macro_rules! impl_real {
+    ($t:ident) => {
+        impl Real for $t {
+            fn zero() -> Self {
+                0.
+            }
+
+            fn one() -> Self {
+                1.
+            }
+
+            fn epsilon() -> Self {
+                $t::EPSILON
+            }
+
+            fn from_f64(value: f64) -> Self {
+                value as $t
+            }
+
+            fn abs(self) -> Self {
+                $t::abs(self)
+            }
+
+            fn is_finite(self) -> bool {
+                $t::is_finite(self)
+            }
+
+            fn is_nan(self) -> bool {
+                $t::is_nan(self)
+            }
+
+            fn sqrt(self) -> Self {
+                $t::sqrt(self)
+            }
+
+            fn powi(self, n: i32) -> Self {
+                $t::powi(self, n)
+            }
+
+            fn powf(self, n: Self) -> Self {
+                $t::powf(self, n)
+            }
+
+            fn exp(self) -> Self {
+                $t::exp(self)
+            }
+
+            fn ln(self) -> Self {
+                $t::ln(self)
+            }
+
+            fn sin(self) -> Self {
+                $t::sin(self)
+            }
+
+            fn cos(self) -> Self {
+                $t::cos(self)
+            }
+        }
+    };
+}
+
+impl_real!(f32);
+impl_real!(f64);
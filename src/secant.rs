@@ -0,0 +1,221 @@
+use std::iter::Iterator;
+use std::ops::{Add, Div, Mul, Sub};
+
+use crate::solve::{check_derivative, solve_with};
+use crate::{Criteria, NewtonError, Real, Solution};
+
+/// An iterator that returns successive iterations of the secant method.
+///
+/// The derivative used by Newton's method is replaced by the slope between the last two iterates,
+/// so only the function itself is needed. Each iteration evaluates the function once.
+///
+/// When the slope vanishes, such as once an exact root is reached, the iterator keeps returning
+/// the current iterate instead of dividing by zero.
+///
+/// # Example
+///
+/// ```
+/// use generic_newton::Secant;
+///
+/// let mut s = Secant::new(0.5, 1., |x: f64| x.cos() - x.powi(3));
+///
+/// assert!((s.nth(10).unwrap() - 0.865474033102).abs() < 1E-11)
+/// ```
+pub struct Secant<T, F>
+where
+    T: Sub<Output = T> + Mul<Output = T> + Div<Output = T> + PartialEq + Copy,
+    F: Fn(T) -> T,
+{
+    previous: T,
+    previous_value: T,
+    current: T,
+    func: F,
+}
+
+impl<T, F> Secant<T, F>
+where
+    T: Sub<Output = T> + Mul<Output = T> + Div<Output = T> + PartialEq + Copy,
+    F: Fn(T) -> T,
+{
+    /// Creates a new `Secant` iterator.
+    ///
+    /// - `first_guess` and `second_guess` are two distinct starting points
+    /// - `func` is the actual function to find the root of
+    pub fn new(first_guess: T, second_guess: T, func: F) -> Self {
+        Secant {
+            previous: first_guess,
+            previous_value: func(first_guess),
+            current: second_guess,
+            func,
+        }
+    }
+
+    /// Iterates until `criteria` are met, and returns the root found.
+    ///
+    /// The secant slope plays the role of the derivative in the returned errors.
+    pub fn solve(self, criteria: &Criteria<T>) -> Result<Solution<T>, NewtonError<T>>
+    where
+        T: Real,
+    {
+        let mut previous = self.previous;
+        let mut previous_value = self.previous_value;
+
+        solve_with(criteria, self.current, &self.func, |x, fx| {
+            let slope = check_derivative(x, (fx - previous_value) / (x - previous))?;
+            previous = x;
+            previous_value = fx;
+            Ok(x - fx / slope)
+        })
+    }
+}
+
+impl<T, F> Iterator for Secant<T, F>
+where
+    T: Sub<Output = T> + Mul<Output = T> + Div<Output = T> + PartialEq + Copy,
+    F: Fn(T) -> T,
+{
+    type Item = T;
+    fn next(&mut self) -> Option<Self::Item> {
+        let func = &self.func;
+
+        let value = func(self.current);
+        if value == self.previous_value {
+            return Some(self.current);
+        }
+
+        let next =
+            self.current - value * (self.current - self.previous) / (value - self.previous_value);
+
+        self.previous = self.current;
+        self.previous_value = value;
+        self.current = next;
+        Some(next)
+    }
+}
+
+/// An iterator that returns successive iterations of Steffensen's method.
+///
+/// The derivative is replaced by the slope `(f(x + f(x)) - f(x)) / f(x)`, which keeps the
+/// quadratic convergence of Newton's method close to the root, at the cost of two function
+/// evaluations per iteration.
+///
+/// When the slope vanishes, such as once an exact root is reached, the iterator keeps returning
+/// the current iterate instead of dividing by zero.
+///
+/// # Example
+///
+/// ```
+/// use generic_newton::Steffensen;
+///
+/// let mut s = Steffensen::new(0.9, |x: f64| x.cos() - x.powi(3));
+///
+/// assert!((s.nth(10).unwrap() - 0.865474033102).abs() < 1E-11)
+/// ```
+pub struct Steffensen<T, F>
+where
+    T: Add<Output = T> + Sub<Output = T> + Mul<Output = T> + Div<Output = T> + PartialEq + Copy,
+    F: Fn(T) -> T,
+{
+    current: T,
+    func: F,
+}
+
+impl<T, F> Steffensen<T, F>
+where
+    T: Add<Output = T> + Sub<Output = T> + Mul<Output = T> + Div<Output = T> + PartialEq + Copy,
+    F: Fn(T) -> T,
+{
+    /// Creates a new `Steffensen` iterator.
+    ///
+    /// - `func` is the actual function to find the root of
+    pub fn new(initial_guess: T, func: F) -> Self {
+        Steffensen {
+            current: initial_guess,
+            func,
+        }
+    }
+
+    /// Iterates until `criteria` are met, and returns the root found.
+    ///
+    /// The slope plays the role of the derivative in the returned errors.
+    pub fn solve(self, criteria: &Criteria<T>) -> Result<Solution<T>, NewtonError<T>>
+    where
+        T: Real,
+    {
+        let func = &self.func;
+
+        solve_with(criteria, self.current, func, |x, fx| {
+            let slope = check_derivative(x, (func(x + fx) - fx) / fx)?;
+            Ok(x - fx / slope)
+        })
+    }
+}
+
+impl<T, F> Iterator for Steffensen<T, F>
+where
+    T: Add<Output = T> + Sub<Output = T> + Mul<Output = T> + Div<Output = T> + PartialEq + Copy,
+    F: Fn(T) -> T,
+{
+    type Item = T;
+    fn next(&mut self) -> Option<Self::Item> {
+        let func = &self.func;
+
+        let value = func(self.current);
+        let shifted = func(self.current + value);
+        if shifted == value {
+            return Some(self.current);
+        }
+
+        let next = self.current - value * value / (shifted - value);
+
+        self.current = next;
+        Some(next)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{Secant, Steffensen};
+    use crate::{Criteria, NewtonError};
+
+    #[test]
+    fn is_generic() {
+        let mut s = Secant::new(0, 2, |x| x - 1);
+        assert_eq!(s.nth(5).unwrap(), 1);
+
+        let mut s = Secant::new(0., 2., |x| x - 1.);
+        assert_eq!(s.nth(5).unwrap(), 1.);
+
+        let mut s = Steffensen::new(0, |x| x - 1);
+        assert_eq!(s.nth(5).unwrap(), 1);
+
+        let mut s = Steffensen::new(0., |x| x - 1.);
+        assert_eq!(s.nth(5).unwrap(), 1.);
+    }
+
+    #[test]
+    fn solve() {
+        let criteria = Criteria::default();
+
+        let solution = Secant::new(1., 2., |x: f64| x * x - 2.)
+            .solve(&criteria)
+            .unwrap();
+        assert!((solution.root - 2f64.sqrt()).abs() < 1E-15);
+
+        let solution = Steffensen::new(1., |x: f64| x * x - 2.)
+            .solve(&criteria)
+            .unwrap();
+        assert!((solution.root - 2f64.sqrt()).abs() < 1E-15);
+    }
+
+    #[test]
+    fn solve_flat() {
+        let criteria = Criteria::default();
+
+        assert_eq!(
+            Secant::new(-1., 1., |x: f64| x * x + 1.).solve(&criteria),
+            Err(NewtonError::ZeroDerivative { at: 1. })
+        );
+    }
+}
@@ -0,0 +1,393 @@
+use std::iter::Iterator;
+
+use crate::linalg::{dot, norm, LinearSolver, LuSolver, Matrix};
+use crate::solve::solve_system_with;
+use crate::{Criteria, NewtonError, Real, Solution};
+
+/// How [`SemismoothNewton`] turns the complementarity conditions `a >= 0`, `b >= 0`, `a·b = 0`
+/// into an equation `φ(a, b) = 0`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reformulation {
+    /// The Fischer–Burmeister function `√(a² + b²) - a - b`, whose square is smooth.
+    FischerBurmeister,
+    /// The function `min(a, b)`, which is cheaper but whose merit function is not smooth.
+    Min,
+}
+
+impl Reformulation {
+    /// The value of `φ(a, b)` and an element `(∂φ/∂a, ∂φ/∂b)` of its generalised gradient.
+    fn evaluate<T: Real>(self, a: T, b: T) -> (T, T, T) {
+        match self {
+            Reformulation::FischerBurmeister => {
+                let radius = (a * a + b * b).sqrt();
+                if radius > T::zero() {
+                    (radius - a - b, a / radius - T::one(), b / radius - T::one())
+                } else {
+                    // Any point of the unit circle can replace `(a, b) / radius` at the kink.
+                    let half = T::from_f64(0.5).sqrt();
+                    (T::zero(), half - T::one(), half - T::one())
+                }
+            }
+            Reformulation::Min => {
+                if a <= b {
+                    (a, T::one(), T::zero())
+                } else {
+                    (b, T::zero(), T::one())
+                }
+            }
+        }
+    }
+}
+
+/// The reformulation `Φ(x)`, whose `i`-th component is `φ(xᵢ, Fᵢ(x))`.
+fn reformulate<T, F>(reformulation: Reformulation, func: &F, x: &[T]) -> Vec<T>
+where
+    T: Real,
+    F: Fn(&[T]) -> Vec<T>,
+{
+    x.iter()
+        .zip(func(x))
+        .map(|(&a, b)| reformulation.evaluate(a, b).0)
+        .collect()
+}
+
+/// Everything about the iteration that is not the current point.
+struct State<S> {
+    reformulation: Reformulation,
+    solver: S,
+}
+
+impl<S> State<S> {
+    /// Computes the next iterate from `x`, where `residual` is `Φ(x)`.
+    ///
+    /// The Newton step for `Φ` uses an element of its generalised Jacobian, and is halved until
+    /// the merit function `½·‖Φ‖²` decreases enough. When that element is singular, or the step
+    /// is not a descent direction, the opposite of the gradient of the merit function is used
+    /// instead.
+    ///
+    /// When no step decreases the merit function, `x` is returned if `Φ(x)`, or the gradient of
+    /// the merit function, is below the rounding errors, and the iteration fails otherwise.
+    fn advance<T, F, J>(
+        &mut self,
+        func: &F,
+        jacobian: &J,
+        x: &[T],
+        residual: &[T],
+    ) -> Result<Vec<T>, NewtonError<Vec<T>>>
+    where
+        T: Real,
+        F: Fn(&[T]) -> Vec<T>,
+        J: Fn(&[T]) -> Matrix<T>,
+        S: LinearSolver<T>,
+    {
+        let n = x.len();
+        let value = func(x);
+        let jacobian = jacobian(x);
+        if !jacobian.is_finite() {
+            return Err(NewtonError::NonFiniteDerivative { at: x.to_vec() });
+        }
+
+        // The rows of `V = Dₐ + D_b·J`.
+        let mut element = Matrix::zeros(n, n);
+        for row in 0..n {
+            let (_, da, db) = self.reformulation.evaluate(x[row], value[row]);
+            for col in 0..n {
+                element[(row, col)] = db * jacobian[(row, col)];
+            }
+            element[(row, row)] = element[(row, row)] + da;
+        }
+
+        // The gradient of the merit function, `Vᵀ·Φ`.
+        let gradient = element.transpose().mul_vec(residual);
+        let merit = dot(residual, residual);
+
+        let rhs: Vec<T> = residual.iter().map(|&r| -r).collect();
+        let newton = self
+            .solver
+            .solve(&element, &rhs)
+            .filter(|step| step.iter().all(|s| s.is_finite()));
+        let (step, slope) = match newton {
+            Some(step) if dot(&gradient, &step) < T::zero() => {
+                let slope = dot(&gradient, &step);
+                (step, slope)
+            }
+            _ => {
+                let step: Vec<T> = gradient.iter().map(|&g| -g).collect();
+                let slope = -dot(&gradient, &gradient);
+                (step, slope)
+            }
+        };
+        let rounding = T::from_f64(16.) * T::epsilon();
+        let scale = (0..n).fold(T::zero(), |acc, row| {
+            let row = norm(element.row(row));
+            acc + row * row
+        });
+        let stationary = norm(residual) <= rounding * (norm(x) + norm(&value))
+            || norm(&gradient) <= rounding * scale.sqrt() * norm(residual);
+        let stalled = || {
+            if stationary {
+                Ok(x.to_vec())
+            } else {
+                Err(NewtonError::LineSearchFailed { at: x.to_vec() })
+            }
+        };
+
+        let descent = slope < T::zero();
+        if !descent {
+            return stalled();
+        }
+
+        let mut damping = T::one();
+        for _ in 0..50 {
+            let next: Vec<T> = x
+                .iter()
+                .zip(&step)
+                .map(|(&x, &s)| x + damping * s)
+                .collect();
+            let next_residual = reformulate(self.reformulation, func, &next);
+            let next_merit = dot(&next_residual, &next_residual);
+            // Both merits are twice `½·‖Φ‖²`.
+            if next_merit.is_finite() && next_merit <= merit + T::from_f64(2E-4) * damping * slope {
+                return Ok(next);
+            }
+            damping = T::from_f64(0.5) * damping;
+        }
+        stalled()
+    }
+}
+
+/// An iterator that returns successive iterations of the semismooth Newton method for the
+/// nonlinear complementarity problem
+///
+/// ```text
+/// x >= 0,    F(x) >= 0,    xᵢ·Fᵢ(x) = 0,
+/// ```
+///
+/// which arises from contact, friction, or the optimality conditions of bound-constrained
+/// problems.
+///
+/// The conditions are reformulated as the equation `Φ(x) = 0`, where `Φᵢ(x) = φ(xᵢ, Fᵢ(x))` is
+/// given by the [`Reformulation`]. `Φ` is not differentiable, but is semismooth, so that Newton's
+/// method using any element of its generalised Jacobian still converges quadratically near a
+/// regular solution. The steps are damped until the merit function `½·‖Φ‖²` decreases enough,
+/// and the linear systems are solved by a [`LinearSolver`], which is a dense LU decomposition by
+/// default.
+///
+/// The iterator stops when the Jacobian of `F` is not finite, or no step decreases the merit
+/// function.
+///
+/// # Example
+///
+/// ```
+/// use generic_newton::{Matrix, SemismoothNewton};
+///
+/// // A linear complementarity problem, F(x) = M·x + q.
+/// let mut n = SemismoothNewton::new(
+///     vec![1., 1.], // Initial guess
+///     |x: &[f64]| vec![2. * x[0] + x[1] - 4., x[0] + 2. * x[1] + 1.], // The function
+///     |_: &[f64]| Matrix::from_rows(&[[2., 1.], [1., 2.]]), // Its Jacobian
+/// );
+///
+/// // x₂ = 0 while F₂(x) = 3.
+/// let root = n.nth(20).unwrap();
+/// assert!((root[0] - 2.).abs() < 1E-12 && root[1].abs() < 1E-12);
+/// ```
+pub struct SemismoothNewton<T, F, J, S = LuSolver>
+where
+    T: Real,
+    F: Fn(&[T]) -> Vec<T>,
+    J: Fn(&[T]) -> Matrix<T>,
+    S: LinearSolver<T>,
+{
+    current: Vec<T>,
+    func: F,
+    jacobian: J,
+    state: State<S>,
+}
+
+impl<T, F, J> SemismoothNewton<T, F, J>
+where
+    T: Real,
+    F: Fn(&[T]) -> Vec<T>,
+    J: Fn(&[T]) -> Matrix<T>,
+{
+    /// Creates a new `SemismoothNewton` iterator, using the
+    /// [`Reformulation::FischerBurmeister`] function.
+    ///
+    /// - `func` is `F`, which must be non-negative where `x` is zero
+    /// - `jacobian` is it's Jacobian matrix.
+    pub fn new(initial_guess: Vec<T>, func: F, jacobian: J) -> Self {
+        SemismoothNewton {
+            current: initial_guess,
+            func,
+            jacobian,
+            state: State {
+                reformulation: Reformulation::FischerBurmeister,
+                solver: LuSolver,
+            },
+        }
+    }
+}
+
+impl<T, F, J, S> SemismoothNewton<T, F, J, S>
+where
+    T: Real,
+    F: Fn(&[T]) -> Vec<T>,
+    J: Fn(&[T]) -> Matrix<T>,
+    S: LinearSolver<T>,
+{
+    /// Sets how the complementarity conditions are reformulated.
+    pub fn with_reformulation(mut self, reformulation: Reformulation) -> Self {
+        self.state.reformulation = reformulation;
+        self
+    }
+
+    /// Replaces the solver used for the linear systems.
+    pub fn with_solver<S2: LinearSolver<T>>(self, solver: S2) -> SemismoothNewton<T, F, J, S2> {
+        SemismoothNewton {
+            current: self.current,
+            func: self.func,
+            jacobian: self.jacobian,
+            state: State {
+                reformulation: self.state.reformulation,
+                solver,
+            },
+        }
+    }
+
+    /// Iterates until `criteria` are met, and returns the solution found.
+    ///
+    /// The residual is `Φ(x)`, so that the residual tolerance of `criteria` bounds how much the
+    /// complementarity conditions are violated. The iteration also stops where `Φ`, or the
+    /// gradient of the merit function, vanishes to working precision. Otherwise, when no step
+    /// decreases the merit function, it fails with [`NewtonError::LineSearchFailed`].
+    pub fn solve(self, criteria: &Criteria<T>) -> Result<Solution<Vec<T>>, NewtonError<Vec<T>>> {
+        let (func, jacobian) = (&self.func, &self.jacobian);
+        let reformulation = self.state.reformulation;
+        let mut state = self.state;
+
+        solve_system_with(
+            criteria,
+            self.current,
+            |x| reformulate(reformulation, func, x),
+            |x, residual| state.advance(func, jacobian, x, residual),
+        )
+    }
+}
+
+impl<T, F, J, S> Iterator for SemismoothNewton<T, F, J, S>
+where
+    T: Real,
+    F: Fn(&[T]) -> Vec<T>,
+    J: Fn(&[T]) -> Matrix<T>,
+    S: LinearSolver<T>,
+{
+    type Item = Vec<T>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let residual = reformulate(self.state.reformulation, &self.func, &self.current);
+        self.current = self
+            .state
+            .advance(&self.func, &self.jacobian, &self.current, &residual)
+            .ok()?;
+        Some(self.current.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{Reformulation, SemismoothNewton};
+    use crate::{Criteria, Matrix, NewtonError};
+
+    /// The Kojima–Shindo problem, whose solutions are `(1, 0, 3, 0)` and `(√6/2, 0, 0, ½)`.
+    fn func(x: &[f64]) -> Vec<f64> {
+        vec![
+            3. * x[0] * x[0] + 2. * x[0] * x[1] + 2. * x[1] * x[1] + x[2] + 3. * x[3] - 6.,
+            2. * x[0] * x[0] + x[0] + x[1] * x[1] + 10. * x[2] + 2. * x[3] - 2.,
+            3. * x[0] * x[0] + x[0] * x[1] + 2. * x[1] * x[1] + 2. * x[2] + 9. * x[3] - 9.,
+            x[0] * x[0] + 3. * x[1] * x[1] + 2. * x[2] + 3. * x[3] - 3.,
+        ]
+    }
+
+    fn jacobian(x: &[f64]) -> Matrix<f64> {
+        Matrix::from_rows(&[
+            [6. * x[0] + 2. * x[1], 2. * x[0] + 4. * x[1], 1., 3.],
+            [4. * x[0] + 1., 2. * x[1], 10., 2.],
+            [6. * x[0] + x[1], x[0] + 4. * x[1], 2., 9.],
+            [2. * x[0], 6. * x[1], 2., 3.],
+        ])
+    }
+
+    #[test]
+    fn kojima_shindo() {
+        let criteria = Criteria {
+            residual_tolerance: 1E-12,
+            ..Criteria::default()
+        };
+        let degenerate = [1.5f64.sqrt(), 0., 0., 0.5];
+        let regular = [1., 0., 3., 0.];
+
+        for &reformulation in &[Reformulation::FischerBurmeister, Reformulation::Min] {
+            for start in &[[1f64, 1., 1., 1.], [0., 0., 0., 0.]] {
+                let solution = SemismoothNewton::new(start.to_vec(), func, jacobian)
+                    .with_reformulation(reformulation)
+                    .solve(&criteria)
+                    .unwrap();
+
+                let close = |expected: &[f64]| {
+                    solution
+                        .root
+                        .iter()
+                        .zip(expected)
+                        .all(|(x, e)| (x - e).abs() < 1E-8)
+                };
+                assert!(close(&degenerate) || close(&regular));
+                let value = func(&solution.root);
+                for (x, f) in solution.root.iter().zip(&value) {
+                    assert!(*x >= -1E-12 && *f >= -1E-12 && (x * f).abs() < 1E-12);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn reformulations_agree_on_linear_problem() {
+        // M is positive definite, so the solution is unique: (0, 1, 0).
+        let m = Matrix::from_rows(&[[4f64, -1., 0.], [-1., 4., -1.], [0., -1., 4.]]);
+        let q = [1., -4., 3.];
+        let func = |x: &[f64]| {
+            m.mul_vec(x)
+                .into_iter()
+                .zip(&q)
+                .map(|(mx, q)| mx + q)
+                .collect::<Vec<f64>>()
+        };
+
+        for &reformulation in &[Reformulation::FischerBurmeister, Reformulation::Min] {
+            let solution = SemismoothNewton::new(vec![1.; 3], func, |_: &[f64]| m.clone())
+                .with_reformulation(reformulation)
+                .solve(&Criteria::default())
+                .unwrap();
+            for (x, expected) in solution.root.iter().zip(&[0., 1., 0.]) {
+                assert!((x - expected).abs() < 1E-14);
+            }
+        }
+    }
+
+    #[test]
+    fn reports_unsolvable_problem() {
+        // x >= 0 and -x - 1 >= 0 have no solution, and the merit function of `min(x, -x - 1)` is
+        // smallest at its kink, x = -½, where no step decreases it.
+        let result = SemismoothNewton::new(
+            vec![0.],
+            |x: &[f64]| vec![-x[0] - 1.],
+            |_: &[f64]| Matrix::from_rows(&[[-1.]]),
+        )
+        .with_reformulation(Reformulation::Min)
+        .solve(&Criteria::default());
+        assert_eq!(
+            result,
+            Err(NewtonError::LineSearchFailed { at: vec![-0.5] })
+        );
+    }
+}
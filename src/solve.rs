@@ -0,0 +1,252 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::linalg::norm;
+use crate::Real;
+
+/// Stopping criteria used when solving until convergence.
+///
+/// The iteration stops successfully as soon as either the residual `|f(x)|` drops below
+/// `residual_tolerance`, or the last step satisfies
+/// `|x_{n+1} - x_n| <= absolute_tolerance + relative_tolerance * |x_{n+1}|`.
+///
+/// For systems of equations, absolute values are replaced by euclidean norms.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Criteria<T> {
+    /// Absolute tolerance on the step size.
+    pub absolute_tolerance: T,
+    /// Tolerance on the step size, relative to the current iterate.
+    pub relative_tolerance: T,
+    /// Tolerance on the absolute value of the residual.
+    pub residual_tolerance: T,
+    /// Maximum number of steps to take before giving up.
+    pub max_iterations: usize,
+}
+
+impl<T: Real> Default for Criteria<T> {
+    fn default() -> Self {
+        let tolerance = T::epsilon() * T::from_f64(64.);
+        Criteria {
+            absolute_tolerance: tolerance,
+            relative_tolerance: tolerance,
+            residual_tolerance: T::zero(),
+            max_iterations: 100,
+        }
+    }
+}
+
+impl<T: Real> Criteria<T> {
+    /// Whether a step from `previous` to `next` is small enough to stop.
+    pub(crate) fn step_converged(&self, previous: T, next: T) -> bool {
+        (next - previous).abs() <= self.absolute_tolerance + self.relative_tolerance * next.abs()
+    }
+
+    /// Whether a step between two vectors is small enough to stop.
+    pub(crate) fn vector_step_converged(&self, previous: &[T], next: &[T]) -> bool {
+        let step: Vec<T> = next.iter().zip(previous).map(|(&n, &p)| n - p).collect();
+        norm(&step) <= self.absolute_tolerance + self.relative_tolerance * norm(next)
+    }
+}
+
+/// A root found by a solver.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Solution<T> {
+    /// The approximation of the root.
+    pub root: T,
+    /// The value of the function at `root`.
+    pub residual: T,
+    /// The number of steps taken to reach `root`.
+    pub iterations: usize,
+}
+
+/// The reasons a solver can fail to converge.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NewtonError<T> {
+    /// The criteria were not met in the allowed number of iterations.
+    MaxIterations {
+        /// The last iterate.
+        last: T,
+        /// The number of steps taken.
+        iterations: usize,
+    },
+    /// The derivative vanished, or the Jacobian is singular, so no step can be taken.
+    ZeroDerivative {
+        /// Where the derivative vanished.
+        at: T,
+    },
+    /// The derivative is infinite or NaN.
+    NonFiniteDerivative {
+        /// Where the derivative was evaluated.
+        at: T,
+    },
+    /// The function returned NaN.
+    NanResidual {
+        /// Where the function was evaluated.
+        at: T,
+    },
+    /// The iterates escaped to infinity or NaN.
+    Divergence {
+        /// The last finite iterate.
+        at: T,
+    },
+    /// No step along the Newton direction, however damped, decreases the residual or the
+    /// objective enough.
+    LineSearchFailed {
+        /// Where the search started.
+        at: T,
+    },
+    /// An input does not have the size the method needs, such as fewer residuals than
+    /// parameters in a least squares problem.
+    DimensionMismatch {
+        /// The size needed, exactly or at least depending on the input.
+        expected: usize,
+        /// The actual size.
+        found: usize,
+    },
+    /// The function has the same sign at both ends of an interval that should bracket a root.
+    NoSignChange {
+        /// The lower end of the interval.
+        lower: T,
+        /// The upper end of the interval.
+        upper: T,
+    },
+}
+
+impl<T: fmt::Debug> fmt::Display for NewtonError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NewtonError::MaxIterations { last, iterations } => write!(
+                f,
+                "no convergence after {} iterations (last iterate: {:?})",
+                iterations, last
+            ),
+            NewtonError::ZeroDerivative { at } => write!(f, "zero derivative at {:?}", at),
+            NewtonError::NonFiniteDerivative { at } => {
+                write!(f, "non-finite derivative at {:?}", at)
+            }
+            NewtonError::NanResidual { at } => write!(f, "NaN residual at {:?}", at),
+            NewtonError::Divergence { at } => write!(f, "divergence after {:?}", at),
+            NewtonError::LineSearchFailed { at } => write!(f, "line search failed at {:?}", at),
+            NewtonError::DimensionMismatch { expected, found } => {
+                write!(f, "expected a size of {}, found {}", expected, found)
+            }
+            NewtonError::NoSignChange { lower, upper } => {
+                write!(f, "no sign change between {:?} and {:?}", lower, upper)
+            }
+        }
+    }
+}
+
+impl<T: fmt::Debug> Error for NewtonError<T> {}
+
+/// Checks that a derivative can be divided by.
+pub(crate) fn check_derivative<T: Real>(at: T, derivative: T) -> Result<T, NewtonError<T>> {
+    if !derivative.is_finite() {
+        Err(NewtonError::NonFiniteDerivative { at })
+    } else if derivative == T::zero() {
+        Err(NewtonError::ZeroDerivative { at })
+    } else {
+        Ok(derivative)
+    }
+}
+
+/// Runs a scalar method until `criteria` are met.
+///
+/// `residual` evaluates the function, and `advance` computes the next iterate from the current
+/// one and its residual. Keeping them apart avoids computing derivatives once converged.
+pub(crate) fn solve_with<T, R, A>(
+    criteria: &Criteria<T>,
+    initial_guess: T,
+    mut residual: R,
+    mut advance: A,
+) -> Result<Solution<T>, NewtonError<T>>
+where
+    T: Real,
+    R: FnMut(T) -> T,
+    A: FnMut(T, T) -> Result<T, NewtonError<T>>,
+{
+    let mut current = initial_guess;
+    let mut step_converged = false;
+    let mut iterations = 0;
+
+    loop {
+        let fx = residual(current);
+        if fx.is_nan() {
+            return Err(NewtonError::NanResidual { at: current });
+        }
+
+        if step_converged || fx.abs() <= criteria.residual_tolerance {
+            return Ok(Solution {
+                root: current,
+                residual: fx,
+                iterations,
+            });
+        }
+
+        if iterations >= criteria.max_iterations {
+            return Err(NewtonError::MaxIterations {
+                last: current,
+                iterations,
+            });
+        }
+
+        let next = advance(current, fx)?;
+        if !next.is_finite() {
+            return Err(NewtonError::Divergence { at: current });
+        }
+
+        step_converged = criteria.step_converged(current, next);
+        current = next;
+        iterations += 1;
+    }
+}
+
+/// Runs a method for systems of equations until `criteria` are met.
+///
+/// This is the counterpart of [`solve_with`] for vectors.
+pub(crate) fn solve_system_with<T, R, A>(
+    criteria: &Criteria<T>,
+    initial_guess: Vec<T>,
+    mut residual: R,
+    mut advance: A,
+) -> Result<Solution<Vec<T>>, NewtonError<Vec<T>>>
+where
+    T: Real,
+    R: FnMut(&[T]) -> Vec<T>,
+    A: FnMut(&[T], &[T]) -> Result<Vec<T>, NewtonError<Vec<T>>>,
+{
+    let mut current = initial_guess;
+    let mut step_converged = false;
+    let mut iterations = 0;
+
+    loop {
+        let fx = residual(&current);
+        if fx.iter().any(|x| x.is_nan()) {
+            return Err(NewtonError::NanResidual { at: current });
+        }
+
+        if step_converged || norm(&fx) <= criteria.residual_tolerance {
+            return Ok(Solution {
+                root: current,
+                residual: fx,
+                iterations,
+            });
+        }
+
+        if iterations >= criteria.max_iterations {
+            return Err(NewtonError::MaxIterations {
+                last: current,
+                iterations,
+            });
+        }
+
+        let next = advance(&current, &fx)?;
+        if next.iter().any(|x| !x.is_finite()) {
+            return Err(NewtonError::Divergence { at: current });
+        }
+
+        step_converged = criteria.vector_step_converged(&current, &next);
+        current = next;
+        iterations += 1;
+    }
+}
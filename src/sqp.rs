@@ -0,0 +1,575 @@
+use std::iter::Iterator;
+
+use crate::linalg::{dot, norm, LinearSolver, LuSolver, Matrix};
+use crate::optimize::stalled_search;
+use crate::solve::solve_system_with;
+use crate::{Criteria, NewtonError, Real, Solution};
+
+/// The merit function [`Sqp`] decreases at each step, deciding how much of the Newton step on
+/// the KKT system is taken.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Merit<T> {
+    /// Always takes the full step, which is Newton's method on the KKT system. It converges
+    /// quadratically close to a solution, but may converge to any stationary point, or diverge.
+    Full,
+    /// The exact penalty `f(x) + μ·‖h(x)‖₁`, starting with the given penalty `μ`, which is
+    /// increased above the multipliers as needed.
+    L1(T),
+    /// The augmented Lagrangian `f(x) + λᵀ·h(x) + ρ/2·‖h(x)‖²`, with the given penalty `ρ`, and
+    /// the updated multipliers `λ`.
+    AugmentedLagrangian(T),
+}
+
+/// The result of [`Sqp::solve`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConstrainedOptimum<T> {
+    /// The minimiser found.
+    pub minimiser: Vec<T>,
+    /// The value of the function at `minimiser`.
+    pub value: T,
+    /// The Lagrange multipliers `λ` of the constraints.
+    pub multipliers: Vec<T>,
+    /// The norm of the constraints `h(x)`.
+    pub infeasibility: T,
+    /// The norm of the gradient of the Lagrangian, `∇f(x) + A(x)ᵀ·λ`.
+    pub stationarity: T,
+    /// The number of iterations performed.
+    pub iterations: usize,
+}
+
+/// The functions defining the problem.
+struct Problem<'a, F, G, H, C, J> {
+    /// The number of variables.
+    size: usize,
+    func: &'a F,
+    gradient: &'a G,
+    hessian: &'a H,
+    constraints: &'a C,
+    jacobian: &'a J,
+}
+
+/// The residual of the KKT system at `point`, made of `x` then `λ`: the gradient of the
+/// Lagrangian, followed by the constraints.
+fn kkt_residual<T, F, G, H, C, J>(problem: &Problem<'_, F, G, H, C, J>, point: &[T]) -> Vec<T>
+where
+    T: Real,
+    G: Fn(&[T]) -> Vec<T>,
+    C: Fn(&[T]) -> Vec<T>,
+    J: Fn(&[T]) -> Matrix<T>,
+{
+    let (x, multipliers) = point.split_at(problem.size);
+    let products = (problem.jacobian)(x).transpose().mul_vec(multipliers);
+
+    (problem.gradient)(x)
+        .into_iter()
+        .zip(products)
+        .map(|(g, p)| g + p)
+        .chain((problem.constraints)(x))
+        .collect()
+}
+
+/// Everything about the iteration that is not the current point.
+struct State<T, S> {
+    merit: Merit<T>,
+    solver: S,
+}
+
+impl<T: Real, S: LinearSolver<T>> State<T, S> {
+    /// The value of the merit function at `x` and `multipliers`, or `None` for [`Merit::Full`].
+    fn merit<F, G, H, C, J>(
+        &self,
+        problem: &Problem<'_, F, G, H, C, J>,
+        x: &[T],
+        multipliers: &[T],
+    ) -> Option<T>
+    where
+        F: Fn(&[T]) -> T,
+        C: Fn(&[T]) -> Vec<T>,
+    {
+        let value = (problem.func)(x);
+        let constraints = (problem.constraints)(x);
+        match self.merit {
+            Merit::Full => None,
+            Merit::L1(penalty) => {
+                Some(value + penalty * constraints.iter().fold(T::zero(), |acc, c| acc + c.abs()))
+            }
+            Merit::AugmentedLagrangian(penalty) => {
+                let half = T::from_f64(0.5);
+                Some(
+                    value
+                        + dot(multipliers, &constraints)
+                        + half * penalty * dot(&constraints, &constraints),
+                )
+            }
+        }
+    }
+
+    /// Computes the next point from `point`, made of `x` then `λ`, where `residual` is the
+    /// residual of the KKT system at `point`, as computed by [`kkt_residual`].
+    ///
+    /// The Hessian of the Lagrangian is shifted until it curves upwards along the Newton step on
+    /// the KKT system. The multipliers are then updated, and the step on `x` is halved until the
+    /// merit function decreases enough. If it never does, `point` is returned when the step is
+    /// below its rounding errors, see [`stalled_search`], and the iteration fails otherwise.
+    fn advance<F, G, H, C, J>(
+        &mut self,
+        problem: &Problem<'_, F, G, H, C, J>,
+        point: &[T],
+        residual: &[T],
+    ) -> Result<Vec<T>, NewtonError<Vec<T>>>
+    where
+        F: Fn(&[T]) -> T,
+        G: Fn(&[T]) -> Vec<T>,
+        H: Fn(&[T], &[T]) -> Matrix<T>,
+        C: Fn(&[T]) -> Vec<T>,
+        J: Fn(&[T]) -> Matrix<T>,
+    {
+        let n = problem.size;
+        let (x, multipliers) = point.split_at(n);
+        let p = multipliers.len();
+
+        let jacobian = (problem.jacobian)(x);
+        let hessian = (problem.hessian)(x, multipliers);
+        if !jacobian.is_finite() || !hessian.is_finite() {
+            return Err(NewtonError::NonFiniteDerivative { at: x.to_vec() });
+        }
+
+        let constraints = &residual[n..];
+        let gradient = (problem.gradient)(x);
+        let rhs: Vec<T> = residual.iter().map(|&r| -r).collect();
+        let beta = T::from_f64(1E-3);
+        let mut shift = T::zero();
+
+        // Shifts `H` by `τ·I` until the Lagrangian curves upwards along the step, and the step
+        // decreases the merit function.
+        let (dx, next_multipliers, slope) = loop {
+            // [H + τ·I  Aᵀ; A 0]
+            let kkt = Matrix::from_fn(n + p, n + p, |row, col| match (row < n, col < n) {
+                (true, true) if row == col => hessian[(row, col)] + shift,
+                (true, true) => hessian[(row, col)],
+                (true, false) => jacobian[(col - n, row)],
+                (false, true) => jacobian[(row - n, col)],
+                (false, false) => T::zero(),
+            });
+            let step = self
+                .solver
+                .solve(&kkt, &rhs)
+                .ok_or_else(|| NewtonError::ZeroDerivative { at: x.to_vec() })?;
+            if step.iter().any(|s| !s.is_finite()) {
+                return Err(NewtonError::NonFiniteDerivative { at: x.to_vec() });
+            }
+            let (dx, dm) = step.split_at(n);
+            let next_multipliers: Vec<T> =
+                multipliers.iter().zip(dm).map(|(&m, &d)| m + d).collect();
+
+            // The directional derivative of the merit function along `dx`.
+            let slope = match &mut self.merit {
+                Merit::Full => {
+                    return Ok(point.iter().zip(&step).map(|(&p, &s)| p + s).collect());
+                }
+                Merit::L1(penalty) => {
+                    // The step is a descent direction if the penalty exceeds the multipliers.
+                    let largest = next_multipliers.iter().fold(T::zero(), |acc, &m| {
+                        if m.abs() > acc {
+                            m.abs()
+                        } else {
+                            acc
+                        }
+                    });
+                    if largest >= *penalty {
+                        *penalty = largest + largest;
+                    }
+                    let violation = constraints.iter().fold(T::zero(), |acc, c| acc + c.abs());
+                    dot(&gradient, dx) - *penalty * violation
+                }
+                Merit::AugmentedLagrangian(penalty) => {
+                    // Since `A·dx = -h`.
+                    let products = jacobian.transpose().mul_vec(&next_multipliers);
+                    let lagrangian: Vec<T> =
+                        gradient.iter().zip(products).map(|(&g, p)| g + p).collect();
+                    dot(&lagrangian, dx) - *penalty * dot(constraints, constraints)
+                }
+            };
+
+            let curvature = dot(dx, &hessian.mul_vec(dx)) + shift * dot(dx, dx);
+            let descent = slope < T::zero() && curvature > T::zero();
+            if descent || shift * T::epsilon() >= T::one() {
+                break (dx.to_vec(), next_multipliers, slope);
+            }
+            shift = if shift + shift > beta {
+                shift + shift
+            } else {
+                beta
+            };
+        };
+
+        let current = self
+            .merit(problem, x, &next_multipliers)
+            .expect("the merit function is defined");
+        let decrease = if slope < T::zero() { slope } else { T::zero() };
+        let mut damping = T::one();
+        for _ in 0..50 {
+            let next: Vec<T> = x.iter().zip(&dx).map(|(&x, &d)| x + damping * d).collect();
+            let value = self
+                .merit(problem, &next, &next_multipliers)
+                .expect("the merit function is defined");
+            let accepted = if decrease < T::zero() {
+                value <= current + T::from_f64(1E-4) * damping * decrease
+            } else {
+                value < current
+            };
+            if value.is_finite() && accepted {
+                return Ok(next.into_iter().chain(next_multipliers).collect());
+            }
+            damping = T::from_f64(0.5) * damping;
+        }
+        let step: Vec<T> = dx
+            .into_iter()
+            .chain(
+                next_multipliers
+                    .iter()
+                    .zip(multipliers)
+                    .map(|(&l, &m)| l - m),
+            )
+            .collect();
+        stalled_search(point, &step)
+    }
+}
+
+/// An iterator that returns successive iterations of sequential quadratic programming,
+/// minimising `f(x)` subject to `h(x) = 0`.
+///
+/// Each iteration solves the KKT system
+///
+/// ```text
+/// [H  Aᵀ] [dx]     [∇f(x) + Aᵀ·λ]
+/// [A  0 ] [dλ] = - [    h(x)    ]
+/// ```
+///
+/// where `H` is the Hessian matrix of the Lagrangian `f(x) + λᵀ·h(x)`, `A` is the Jacobian
+/// matrix of `h`, and `λ` are the Lagrange multipliers, which is Newton's method on the
+/// conditions for a constrained minimum. Away from a minimum, a multiple of the identity is added
+/// to `H` until the Lagrangian curves upwards along `dx`, the multipliers move to `λ + dλ`, and
+/// `dx` is damped until a [`Merit`] function decreases enough. The linear systems are solved by a
+/// [`LinearSolver`], which is a dense LU decomposition by default.
+///
+/// The iterator stops when a linear system is singular, which happens when the constraints are
+/// not independent, or when no step decreases the merit function.
+///
+/// # Example
+///
+/// ```
+/// use generic_newton::{Matrix, Sqp};
+///
+/// // The point of the unit circle closest to (2, 1).
+/// let mut n = Sqp::new(
+///     vec![0., 1.], // Initial guess
+///     |x: &[f64]| (x[0] - 2.).powi(2) + (x[1] - 1.).powi(2), // The function
+///     |x: &[f64]| vec![2. * (x[0] - 2.), 2. * (x[1] - 1.)], // Its gradient
+///     |_: &[f64], l: &[f64]| Matrix::from_rows(&[
+///         [2. + 2. * l[0], 0.],
+///         [0., 2. + 2. * l[0]],
+///     ]), // The Hessian of the Lagrangian
+///     |x: &[f64]| vec![x[0] * x[0] + x[1] * x[1] - 1.], // The constraints
+///     |x: &[f64]| Matrix::from_rows(&[[2. * x[0], 2. * x[1]]]), // Their Jacobian
+/// );
+///
+/// let minimiser = n.nth(20).unwrap();
+/// let expected = 5f64.sqrt();
+/// assert!((minimiser[0] - 2. / expected).abs() < 1E-12);
+/// assert!((minimiser[1] - 1. / expected).abs() < 1E-12);
+/// ```
+pub struct Sqp<T, F, G, H, C, J, S = LuSolver>
+where
+    T: Real,
+    F: Fn(&[T]) -> T,
+    G: Fn(&[T]) -> Vec<T>,
+    H: Fn(&[T], &[T]) -> Matrix<T>,
+    C: Fn(&[T]) -> Vec<T>,
+    J: Fn(&[T]) -> Matrix<T>,
+    S: LinearSolver<T>,
+{
+    current: Vec<T>,
+    /// The multipliers, once initialised.
+    multipliers: Option<Vec<T>>,
+    func: F,
+    gradient: G,
+    hessian: H,
+    constraints: C,
+    jacobian: J,
+    state: State<T, S>,
+}
+
+impl<T, F, G, H, C, J> Sqp<T, F, G, H, C, J>
+where
+    T: Real,
+    F: Fn(&[T]) -> T,
+    G: Fn(&[T]) -> Vec<T>,
+    H: Fn(&[T], &[T]) -> Matrix<T>,
+    C: Fn(&[T]) -> Vec<T>,
+    J: Fn(&[T]) -> Matrix<T>,
+{
+    /// Creates a new `Sqp` iterator, using the [`Merit::L1`] merit function with a unit penalty.
+    ///
+    /// - `func` is the actual function to minimise
+    /// - `gradient` is it's gradient
+    /// - `hessian(x, λ)` is the Hessian matrix of the Lagrangian `f(x) + λᵀ·h(x)`
+    /// - `constraints` is `h`, whose components must all vanish
+    /// - `jacobian` is the Jacobian matrix of `h`.
+    ///
+    /// The multipliers start at zero.
+    pub fn new(
+        initial_guess: Vec<T>,
+        func: F,
+        gradient: G,
+        hessian: H,
+        constraints: C,
+        jacobian: J,
+    ) -> Self {
+        Sqp {
+            current: initial_guess,
+            multipliers: None,
+            func,
+            gradient,
+            hessian,
+            constraints,
+            jacobian,
+            state: State {
+                merit: Merit::L1(T::one()),
+                solver: LuSolver,
+            },
+        }
+    }
+}
+
+impl<T, F, G, H, C, J, S> Sqp<T, F, G, H, C, J, S>
+where
+    T: Real,
+    F: Fn(&[T]) -> T,
+    G: Fn(&[T]) -> Vec<T>,
+    H: Fn(&[T], &[T]) -> Matrix<T>,
+    C: Fn(&[T]) -> Vec<T>,
+    J: Fn(&[T]) -> Matrix<T>,
+    S: LinearSolver<T>,
+{
+    /// Sets the merit function.
+    pub fn with_merit(mut self, merit: Merit<T>) -> Self {
+        self.state.merit = merit;
+        self
+    }
+
+    /// Sets the initial multipliers.
+    pub fn with_multipliers(mut self, multipliers: Vec<T>) -> Self {
+        self.multipliers = Some(multipliers);
+        self
+    }
+
+    /// Replaces the solver used for the linear systems.
+    ///
+    /// The KKT matrix is symmetric but indefinite, so that [`CholeskySolver`] cannot be used.
+    ///
+    /// [`CholeskySolver`]: crate::CholeskySolver
+    pub fn with_solver<S2: LinearSolver<T>>(self, solver: S2) -> Sqp<T, F, G, H, C, J, S2> {
+        Sqp {
+            current: self.current,
+            multipliers: self.multipliers,
+            func: self.func,
+            gradient: self.gradient,
+            hessian: self.hessian,
+            constraints: self.constraints,
+            jacobian: self.jacobian,
+            state: State {
+                merit: self.state.merit,
+                solver,
+            },
+        }
+    }
+
+    /// The current `x` followed by the multipliers, which must be as many as the constraints.
+    fn point(&self) -> Result<Vec<T>, NewtonError<Vec<T>>> {
+        let p = (self.constraints)(&self.current).len();
+        let multipliers = self
+            .multipliers
+            .clone()
+            .unwrap_or_else(|| vec![T::zero(); p]);
+        if multipliers.len() != p {
+            return Err(NewtonError::DimensionMismatch {
+                expected: p,
+                found: multipliers.len(),
+            });
+        }
+        Ok(self.current.iter().copied().chain(multipliers).collect())
+    }
+
+    /// Iterates until `criteria` are met, and returns the minimiser found.
+    ///
+    /// The residual tolerance of `criteria` applies to the norm of the residual of the KKT system,
+    /// and the step tolerances to `x` and the multipliers together. A singular KKT matrix is
+    /// reported as [`NewtonError::ZeroDerivative`], and initial multipliers that are not as many
+    /// as the constraints as [`NewtonError::DimensionMismatch`]. The iteration also stops when
+    /// the steps fall below the rounding errors of the iterates. Otherwise, when no step
+    /// decreases the merit function, it fails with [`NewtonError::LineSearchFailed`].
+    pub fn solve(
+        mut self,
+        criteria: &Criteria<T>,
+    ) -> Result<ConstrainedOptimum<T>, NewtonError<Vec<T>>> {
+        let point = self.point()?;
+        let problem = Problem {
+            size: self.current.len(),
+            func: &self.func,
+            gradient: &self.gradient,
+            hessian: &self.hessian,
+            constraints: &self.constraints,
+            jacobian: &self.jacobian,
+        };
+        let state = &mut self.state;
+
+        let Solution {
+            root,
+            residual,
+            iterations,
+        } = solve_system_with(
+            criteria,
+            point,
+            |point| kkt_residual(&problem, point),
+            |point, residual| state.advance(&problem, point, residual),
+        )?;
+
+        let n = problem.size;
+        let (x, multipliers) = root.split_at(n);
+        Ok(ConstrainedOptimum {
+            value: (self.func)(x),
+            minimiser: x.to_vec(),
+            multipliers: multipliers.to_vec(),
+            stationarity: norm(&residual[..n]),
+            infeasibility: norm(&residual[n..]),
+            iterations,
+        })
+    }
+}
+
+impl<T, F, G, H, C, J, S> Iterator for Sqp<T, F, G, H, C, J, S>
+where
+    T: Real,
+    F: Fn(&[T]) -> T,
+    G: Fn(&[T]) -> Vec<T>,
+    H: Fn(&[T], &[T]) -> Matrix<T>,
+    C: Fn(&[T]) -> Vec<T>,
+    J: Fn(&[T]) -> Matrix<T>,
+    S: LinearSolver<T>,
+{
+    type Item = Vec<T>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let point = self.point().ok()?;
+        let problem = Problem {
+            size: self.current.len(),
+            func: &self.func,
+            gradient: &self.gradient,
+            hessian: &self.hessian,
+            constraints: &self.constraints,
+            jacobian: &self.jacobian,
+        };
+        let residual = kkt_residual(&problem, &point);
+        let mut next = self.state.advance(&problem, &point, &residual).ok()?;
+
+        self.multipliers = Some(next.split_off(self.current.len()));
+        self.current = next;
+        Some(self.current.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{ConstrainedOptimum, Merit, Sqp};
+    use crate::{Criteria, Matrix, NewtonError};
+
+    /// Minimises `x + y` on the unit circle, whose minimum is at `-(1, 1)/√2`, and maximum at
+    /// `(1, 1)/√2`.
+    fn solve(
+        start: Vec<f64>,
+        multiplier: f64,
+        merit: Merit<f64>,
+        criteria: &Criteria<f64>,
+    ) -> ConstrainedOptimum<f64> {
+        Sqp::new(
+            start,
+            |x: &[f64]| x[0] + x[1],
+            |_: &[f64]| vec![1., 1.],
+            |_: &[f64], l: &[f64]| Matrix::from_rows(&[[2. * l[0], 0.], [0., 2. * l[0]]]),
+            |x: &[f64]| vec![x[0] * x[0] + x[1] * x[1] - 1.],
+            |x: &[f64]| Matrix::from_rows(&[[2. * x[0], 2. * x[1]]]),
+        )
+        .with_merit(merit)
+        .with_multipliers(vec![multiplier])
+        .solve(criteria)
+        .unwrap()
+    }
+
+    #[test]
+    fn merits_agree() {
+        let criteria = Criteria {
+            residual_tolerance: 1E-12,
+            ..Criteria::default()
+        };
+        let expected = -0.5f64.sqrt();
+
+        for merit in &[Merit::Full, Merit::L1(1.), Merit::AugmentedLagrangian(1.)] {
+            let optimum = solve(vec![-1., -0.5], 1., *merit, &criteria);
+            assert!((optimum.minimiser[0] - expected).abs() < 1E-12);
+            assert!((optimum.minimiser[1] - expected).abs() < 1E-12);
+            assert!((optimum.multipliers[0] + expected).abs() < 1E-12);
+            assert!(optimum.infeasibility <= 1E-12);
+        }
+    }
+
+    #[test]
+    fn merit_avoids_maximum() {
+        let criteria = Criteria::default();
+        let start = vec![1., 0.5];
+
+        // Newton's method on the KKT system finds the nearest stationary point, the maximum.
+        let full = solve(start.clone(), 1., Merit::Full, &criteria);
+        assert!((full.value - 2f64.sqrt()).abs() < 1E-12);
+
+        for merit in &[Merit::L1(1.), Merit::AugmentedLagrangian(1.)] {
+            let optimum = solve(start.clone(), 1., *merit, &criteria);
+            assert!((optimum.value + 2f64.sqrt()).abs() < 1E-12);
+        }
+    }
+
+    #[test]
+    fn reports_failures() {
+        // Minimises x² subject to y = 0, with a gradient of the wrong sign, so that the merit
+        // functions increase along every step.
+        let problem = |merit: Merit<f64>, multipliers: Vec<f64>| {
+            Sqp::new(
+                vec![1., 0.],
+                |x: &[f64]| x[0] * x[0],
+                |x: &[f64]| vec![-2. * x[0], 0.],
+                |_: &[f64], _: &[f64]| Matrix::from_rows(&[[2., 0.], [0., 0.]]),
+                |x: &[f64]| vec![x[1]],
+                |_: &[f64]| Matrix::from_rows(&[[0., 1.]]),
+            )
+            .with_merit(merit)
+            .with_multipliers(multipliers)
+            .solve(&Criteria::default())
+        };
+
+        for &merit in &[Merit::L1(1.), Merit::AugmentedLagrangian(1.)] {
+            assert_eq!(
+                problem(merit, vec![0.]),
+                Err(NewtonError::LineSearchFailed {
+                    at: vec![1., 0., 0.]
+                })
+            );
+        }
+        assert_eq!(
+            problem(Merit::Full, vec![0., 0.]),
+            Err(NewtonError::DimensionMismatch {
+                expected: 1,
+                found: 2
+            })
+        );
+    }
+}
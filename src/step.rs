@@ -0,0 +1,43 @@
+use std::iter::Iterator;
+use std::ops::{Div, Sub};
+
+use crate::Newton;
+
+/// Everything computed during a single Newton iteration.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Step<T> {
+    /// The point the step was taken from.
+    pub previous: T,
+    /// The value of the function at `previous`.
+    pub value: T,
+    /// The value of the derivative at `previous`.
+    pub derivative: T,
+    /// The Newton step `value / derivative`, that is subtracted from `previous`.
+    pub step: T,
+    /// The new iterate.
+    pub next: T,
+}
+
+/// An iterator over the [`Step`]s of a [`Newton`] iterator.
+///
+/// This is created by [`Newton::steps`], and advances the underlying iterator.
+pub struct Steps<'a, T, F, DF>
+where
+    T: Div<Output = T> + Sub<Output = T> + Copy,
+    F: Fn(T) -> T,
+    DF: Fn(T) -> T,
+{
+    pub(crate) newton: &'a mut Newton<T, F, DF>,
+}
+
+impl<'a, T, F, DF> Iterator for Steps<'a, T, F, DF>
+where
+    T: Div<Output = T> + Sub<Output = T> + Copy,
+    F: Fn(T) -> T,
+    DF: Fn(T) -> T,
+{
+    type Item = Step<T>;
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.newton.advance())
+    }
+}
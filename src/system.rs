@@ -0,0 +1,273 @@
+use std::iter::Iterator;
+
+use crate::linalg::{LinearSolver, LuSolver, Matrix};
+use crate::solve::solve_system_with;
+use crate::{Criteria, NewtonError, Real, Solution};
+
+/// An iterator that returns successive iterations of Newton's method for systems of equations.
+///
+/// Each iteration solves `J(x)·d = F(x)` and moves to `x - d`, where `J` is the Jacobian matrix of
+/// `F`. The linear systems are solved by a [`LinearSolver`], which is a dense LU decomposition by
+/// default.
+///
+/// The iterator stops when the Jacobian is singular.
+///
+/// # Example
+///
+/// ```
+/// use generic_newton::{Matrix, NewtonSystem};
+///
+/// // The intersections of the unit circle and the line y = x.
+/// let mut n = NewtonSystem::new(
+///     vec![1., 0.], // Initial guess
+///     |x: &[f64]| vec![x[0] * x[0] + x[1] * x[1] - 1., x[0] - x[1]], // The actual function
+///     |x: &[f64]| Matrix::from_rows(&[[2. * x[0], 2. * x[1]], [1., -1.]]), // Its Jacobian
+/// );
+///
+/// let root = n.nth(10).unwrap();
+/// assert!((root[0] - 0.5f64.sqrt()).abs() < 1E-15);
+/// assert!((root[1] - 0.5f64.sqrt()).abs() < 1E-15);
+/// ```
+pub struct NewtonSystem<T, F, J, S = LuSolver>
+where
+    T: Real,
+    F: Fn(&[T]) -> Vec<T>,
+    J: Fn(&[T]) -> Matrix<T>,
+    S: LinearSolver<T>,
+{
+    current: Vec<T>,
+    func: F,
+    jacobian: J,
+    solver: S,
+}
+
+impl<T, F, J> NewtonSystem<T, F, J>
+where
+    T: Real,
+    F: Fn(&[T]) -> Vec<T>,
+    J: Fn(&[T]) -> Matrix<T>,
+{
+    /// Creates a new `NewtonSystem` iterator.
+    ///
+    /// - `func` is the actual function to find the root of
+    /// - `jacobian` is it's Jacobian matrix, whose `(i, j)` entry is the derivative of the `i`-th
+    ///   component with respect to the `j`-th variable.
+    pub fn new(initial_guess: Vec<T>, func: F, jacobian: J) -> Self {
+        NewtonSystem {
+            current: initial_guess,
+            func,
+            jacobian,
+            solver: LuSolver,
+        }
+    }
+}
+
+impl<T, F, J, S> NewtonSystem<T, F, J, S>
+where
+    T: Real,
+    F: Fn(&[T]) -> Vec<T>,
+    J: Fn(&[T]) -> Matrix<T>,
+    S: LinearSolver<T>,
+{
+    /// Replaces the solver used for the linear systems.
+    pub fn with_solver<S2: LinearSolver<T>>(self, solver: S2) -> NewtonSystem<T, F, J, S2> {
+        NewtonSystem {
+            current: self.current,
+            func: self.func,
+            jacobian: self.jacobian,
+            solver,
+        }
+    }
+
+    /// Iterates until `criteria` are met, and returns the root found.
+    ///
+    /// A singular Jacobian is reported as [`NewtonError::ZeroDerivative`], and a residual or
+    /// Jacobian whose size differs from that of `x` as [`NewtonError::DimensionMismatch`].
+    pub fn solve(
+        mut self,
+        criteria: &Criteria<T>,
+    ) -> Result<Solution<Vec<T>>, NewtonError<Vec<T>>> {
+        let jacobian = &self.jacobian;
+        let solver = &mut self.solver;
+
+        solve_system_with(criteria, self.current, &self.func, |x, fx| {
+            let step = newton_step(solver, &jacobian(x), x, fx)?;
+            Ok(x.iter().zip(step).map(|(&x, d)| x - d).collect())
+        })
+    }
+}
+
+/// Checks that `jacobian` has `rows` rows and `cols` columns.
+pub(crate) fn check_shape<T: Real>(
+    jacobian: &Matrix<T>,
+    rows: usize,
+    cols: usize,
+) -> Result<(), NewtonError<Vec<T>>> {
+    if jacobian.rows() != rows {
+        return Err(NewtonError::DimensionMismatch {
+            expected: rows,
+            found: jacobian.rows(),
+        });
+    }
+    if jacobian.cols() != cols {
+        return Err(NewtonError::DimensionMismatch {
+            expected: cols,
+            found: jacobian.cols(),
+        });
+    }
+    Ok(())
+}
+
+/// Solves `jacobian · d = value` for the Newton step `d` at `x`.
+pub(crate) fn newton_step<T: Real, S: LinearSolver<T>>(
+    solver: &mut S,
+    jacobian: &Matrix<T>,
+    x: &[T],
+    value: &[T],
+) -> Result<Vec<T>, NewtonError<Vec<T>>> {
+    if value.len() != x.len() {
+        return Err(NewtonError::DimensionMismatch {
+            expected: x.len(),
+            found: value.len(),
+        });
+    }
+    check_shape(jacobian, x.len(), x.len())?;
+    if !jacobian.is_finite() {
+        return Err(NewtonError::NonFiniteDerivative { at: x.to_vec() });
+    }
+    solver
+        .solve(jacobian, value)
+        .ok_or_else(|| NewtonError::ZeroDerivative { at: x.to_vec() })
+}
+
+/// Approximates the Jacobian of `func` at `x` by forward differences, where `value` is `func(x)`.
+///
+/// The `j`-th variable is perturbed by `step · max(1, |x_j|)`.
+pub(crate) fn finite_difference_jacobian<T, F>(func: &F, x: &[T], value: &[T], step: T) -> Matrix<T>
+where
+    T: Real,
+    F: Fn(&[T]) -> Vec<T>,
+{
+    let mut jacobian = Matrix::zeros(value.len(), x.len());
+    let mut perturbed = x.to_vec();
+    for col in 0..x.len() {
+        let scale = x[col].abs();
+        let h = step * if scale > T::one() { scale } else { T::one() };
+        perturbed[col] = x[col] + h;
+        for (row, (&p, &v)) in func(&perturbed).iter().zip(value).enumerate() {
+            jacobian[(row, col)] = (p - v) / h;
+        }
+        perturbed[col] = x[col];
+    }
+    jacobian
+}
+
+impl<T, F, J, S> Iterator for NewtonSystem<T, F, J, S>
+where
+    T: Real,
+    F: Fn(&[T]) -> Vec<T>,
+    J: Fn(&[T]) -> Matrix<T>,
+    S: LinearSolver<T>,
+{
+    type Item = Vec<T>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let func = &self.func;
+        let jacobian = &self.jacobian;
+
+        let x = &self.current;
+        let step = newton_step(&mut self.solver, &jacobian(x), x, &func(x)).ok()?;
+        for (x, d) in self.current.iter_mut().zip(step) {
+            *x = *x - d;
+        }
+        Some(self.current.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::NewtonSystem;
+    use crate::linalg::{LinearSolver, LuSolver, Matrix};
+    use crate::{Criteria, NewtonError};
+
+    fn func(x: &[f64]) -> Vec<f64> {
+        vec![
+            x[0] * x[0] + x[1] * x[1] + x[2] * x[2] - 3.,
+            x[0] * x[1] - x[2],
+            x[0] - x[1] + x[2] - 1.,
+        ]
+    }
+
+    fn jacobian(x: &[f64]) -> Matrix<f64> {
+        Matrix::from_rows(&[
+            [2. * x[0], 2. * x[1], 2. * x[2]],
+            [x[1], x[0], -1.],
+            [1., -1., 1.],
+        ])
+    }
+
+    #[test]
+    fn solves_system() {
+        let solution = NewtonSystem::new(vec![1.5, 0.5, 0.5], func, jacobian)
+            .solve(&Criteria::default())
+            .unwrap();
+
+        for (x, expected) in solution.root.iter().zip(&[1., 1., 1.]) {
+            assert!((x - expected).abs() < 1E-14);
+        }
+        assert!(solution.iterations < 10);
+    }
+
+    #[test]
+    fn custom_solver() {
+        struct Counting(usize);
+
+        impl LinearSolver<f64> for Counting {
+            fn solve(&mut self, matrix: &Matrix<f64>, rhs: &[f64]) -> Option<Vec<f64>> {
+                self.0 += 1;
+                LuSolver.solve(matrix, rhs)
+            }
+        }
+
+        let mut n = NewtonSystem::new(vec![1.5, 0.5, 0.5], func, jacobian).with_solver(Counting(0));
+        n.nth(4);
+        assert_eq!(n.solver.0, 5);
+    }
+
+    #[test]
+    fn singular_jacobian() {
+        let criteria = Criteria::default();
+        assert_eq!(
+            NewtonSystem::new(vec![0., 0., 0.], func, jacobian).solve(&criteria),
+            Err(NewtonError::ZeroDerivative {
+                at: vec![0., 0., 0.]
+            })
+        );
+        assert!(NewtonSystem::new(vec![0., 0., 0.], func, jacobian)
+            .next()
+            .is_none());
+    }
+
+    #[test]
+    fn mismatched_sizes() {
+        let criteria = Criteria::default();
+        // Three equations in two unknowns.
+        let over = |x: &[f64]| vec![x[0], x[1], x[0] * x[1]];
+        let over_jacobian = |x: &[f64]| Matrix::from_rows(&[[1., 0.], [0., 1.], [x[1], x[0]]]);
+        assert_eq!(
+            NewtonSystem::new(vec![1., 1.], over, over_jacobian).solve(&criteria),
+            Err(NewtonError::DimensionMismatch {
+                expected: 2,
+                found: 3
+            })
+        );
+        let square = |_: &[f64]| Matrix::identity(2);
+        assert_eq!(
+            NewtonSystem::new(vec![2., 1., 0.], func, square).solve(&criteria),
+            Err(NewtonError::DimensionMismatch {
+                expected: 3,
+                found: 2
+            })
+        );
+    }
+}
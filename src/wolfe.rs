@@ -0,0 +1,219 @@
+use crate::linalg::dot;
+use crate::Real;
+
+/// A line search enforcing the strong Wolfe conditions, used by the optimisers.
+///
+/// A step `α` along a descent direction `d` from `x` is accepted when it decreases the function
+/// enough, `f(x + α·d) <= f(x) + c₁·α·∇f(x)·d`, and the slope flattens enough,
+/// `|∇f(x + α·d)·d| <= c₂·|∇f(x)·d|`. Acceptable steps are first bracketed by doubling the step,
+/// then found by cubic interpolation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Wolfe<T> {
+    /// The sufficient decrease parameter `c₁`, between zero and `c₂`.
+    pub sufficient_decrease: T,
+    /// The curvature parameter `c₂`, between `c₁` and one.
+    pub curvature: T,
+    /// The maximum number of evaluations of the function.
+    pub max_evaluations: usize,
+}
+
+impl<T: Real> Default for Wolfe<T> {
+    fn default() -> Self {
+        Wolfe {
+            sufficient_decrease: T::from_f64(1E-4),
+            curvature: T::from_f64(0.9),
+            max_evaluations: 50,
+        }
+    }
+}
+
+/// A point along the search direction.
+#[derive(Debug, Clone)]
+pub(crate) struct Trial<T> {
+    /// The step `α`.
+    pub(crate) step: T,
+    /// The point `x + α·d`.
+    pub(crate) point: Vec<T>,
+    /// The value of the function at `point`.
+    pub(crate) value: T,
+    /// The gradient at `point`.
+    pub(crate) gradient: Vec<T>,
+    /// The slope `∇f(x + α·d)·d`.
+    pub(crate) slope: T,
+}
+
+impl<T: Real> Wolfe<T> {
+    /// Searches along `direction` from `x`, where `value` and `gradient` are the value and gradient
+    /// of the function at `x`, starting from the step `initial`. `objective` computes both the
+    /// value and the gradient of the function.
+    ///
+    /// If no step satisfies the strong Wolfe conditions within the allowed evaluations, the best
+    /// step decreasing the function enough is returned. `None` is returned if there is none, or
+    /// if `direction` is not a descent direction.
+    pub(crate) fn search<O>(
+        &self,
+        objective: &O,
+        x: &[T],
+        value: T,
+        gradient: &[T],
+        direction: &[T],
+        initial: T,
+    ) -> Option<Trial<T>>
+    where
+        O: Fn(&[T]) -> (T, Vec<T>),
+    {
+        let slope = dot(gradient, direction);
+        let descent = slope < T::zero();
+        if !descent {
+            return None;
+        }
+
+        let evaluate = |step: T| {
+            let point: Vec<T> = x
+                .iter()
+                .zip(direction)
+                .map(|(&x, &d)| x + step * d)
+                .collect();
+            let (value, gradient) = objective(&point);
+            Trial {
+                step,
+                value,
+                slope: dot(&gradient, direction),
+                point,
+                gradient,
+            }
+        };
+        let start = Trial {
+            step: T::zero(),
+            point: x.to_vec(),
+            value,
+            gradient: gradient.to_vec(),
+            slope,
+        };
+
+        let decreases =
+            |trial: &Trial<T>| trial.value <= value + self.sufficient_decrease * trial.step * slope;
+        let flattens = |trial: &Trial<T>| trial.slope.abs() <= -self.curvature * slope;
+
+        // Brackets an acceptable step between `low`, which decreases the function enough, and
+        // `high`.
+        let mut evaluations = 0;
+        let mut previous = start;
+        let mut step = initial;
+        let (mut low, mut high) = loop {
+            if evaluations >= self.max_evaluations {
+                return best(previous);
+            }
+            let trial = evaluate(step);
+            evaluations += 1;
+            if !trial.value.is_finite() || !decreases(&trial) || trial.value >= previous.value {
+                break (previous, trial);
+            }
+            if flattens(&trial) {
+                return Some(trial);
+            }
+            if trial.slope >= T::zero() {
+                break (trial, previous);
+            }
+            step = trial.step + trial.step;
+            previous = trial;
+        };
+
+        // Shrinks the bracket until an acceptable step is found.
+        while evaluations < self.max_evaluations {
+            let step = interpolate(&low, &high);
+            if (high.step - low.step).abs() <= T::epsilon() * (low.step.abs() + high.step.abs()) {
+                break;
+            }
+
+            let trial = evaluate(step);
+            evaluations += 1;
+            if !trial.value.is_finite() || !decreases(&trial) || trial.value >= low.value {
+                high = trial;
+            } else {
+                if flattens(&trial) {
+                    return Some(trial);
+                }
+                if trial.slope * (high.step - low.step) >= T::zero() {
+                    high = low;
+                }
+                low = trial;
+            }
+        }
+        best(low)
+    }
+}
+
+/// The step `trial` if it moves at all.
+fn best<T: Real>(trial: Trial<T>) -> Option<Trial<T>> {
+    if trial.step > T::zero() {
+        Some(trial)
+    } else {
+        None
+    }
+}
+
+/// The minimiser of the cubic interpolating the values and slopes at both ends, falling back to
+/// bisection when it lies too close to an end.
+fn interpolate<T: Real>(low: &Trial<T>, high: &Trial<T>) -> T {
+    let (a, b) = (low.step, high.step);
+    let half = T::from_f64(0.5);
+    let bisection = a + half * (b - a);
+
+    if !high.value.is_finite() || !high.slope.is_finite() {
+        return bisection;
+    }
+
+    let d1 = low.slope + high.slope - T::from_f64(3.) * (low.value - high.value) / (a - b);
+    let discriminant = d1 * d1 - low.slope * high.slope;
+    if discriminant < T::zero() {
+        return bisection;
+    }
+    let d2 = if b > a {
+        discriminant.sqrt()
+    } else {
+        -discriminant.sqrt()
+    };
+    let step = b - (b - a) * (high.slope + d2 - d1) / (high.slope - low.slope + d2 + d2);
+
+    // Keeps the new step away from both ends.
+    let margin = T::from_f64(0.1) * (b - a).abs();
+    let (lower, upper) = if a < b { (a, b) } else { (b, a) };
+    if step.is_finite() && step > lower + margin && step < upper - margin {
+        step
+    } else {
+        bisection
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::Wolfe;
+
+    #[test]
+    fn satisfies_conditions() {
+        // A one-dimensional function whose minimum along the direction is far away.
+        let func = |x: &[f64]| (x[0] - 10.).powi(2) + x[0].sin();
+        let grad = |x: &[f64]| vec![2. * (x[0] - 10.) + x[0].cos()];
+        let objective = |x: &[f64]| (func(x), grad(x));
+
+        let wolfe = Wolfe {
+            curvature: 0.1,
+            ..Wolfe::default()
+        };
+        let x = [0.];
+        let gradient = grad(&x);
+        let trial = wolfe
+            .search(&objective, &x, func(&x), &gradient, &[1.], 0.01)
+            .unwrap();
+
+        assert!(trial.value <= func(&x) + 1E-4 * trial.step * gradient[0]);
+        assert!(trial.slope.abs() <= 0.1 * gradient[0].abs());
+
+        // Not a descent direction.
+        assert!(wolfe
+            .search(&objective, &x, func(&x), &gradient, &[-1.], 1.)
+            .is_none());
+    }
+}